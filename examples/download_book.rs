@@ -1,13 +1,17 @@
-use color_eyre::eyre::{Error, Result};
-use godric::scene::goodreads::book::Book;
+use color_eyre::eyre::{ContextCompat, Result};
+use godric::source::{self, Item};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let link = url::Url::parse(
-        "https://www.goodreads.com/book/show/72193.Harry_Potter_and_the_Philosopher_s_Stone",
-    )?;
-    let client = reqwest::Client::new();
-    let book = Book::fetch(link, &client).await?;
-    dbg!(book);
+    let goodreads = source::find("Goodreads").context("Goodreads isn't a known source")?;
+    let book = Item {
+        id: "https://www.goodreads.com/book/show/72193.Harry_Potter_and_the_Philosopher_s_Stone"
+            .to_string(),
+        title: "Harry Potter and the Philosopher's Stone".to_string(),
+        date_added: None,
+        details: None,
+    };
+    let details = goodreads.fetch_details(&book).await?;
+    dbg!(details);
     Ok(())
 }
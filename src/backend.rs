@@ -1,61 +1,103 @@
-pub mod goodreads;
+pub mod home;
 pub mod uninitialized;
+pub mod welcome;
+
+use std::collections::VecDeque;
 
 use color_eyre::{Result, eyre::ContextCompat};
 use tokio::sync::mpsc;
 
 use self::uninitialized::Uninitialized;
-use crate::common::browser;
+use crate::common::{browser, diagnostics};
 
 #[derive(thiserror::Error, Debug)]
 pub enum Error {
     #[error(transparent)]
-    Goodreads(#[from] goodreads::Error),
+    Welcome(#[from] welcome::Error),
+    #[error(transparent)]
+    Home(#[from] home::Error),
     #[error("Invalid message ({message}) for state {state}")]
     InvalidState { state: String, message: String },
     #[error("Unhandled message: {0}")]
     UnhandledMessage(String),
-    #[error("Not initialized")]
-    Uninitialized(uninitialized::Error),
-    #[error("Backend unable to reach UI")]
+    #[error("Failed to initialize")]
+    Uninitialized(#[source] uninitialized::Error),
+    #[error("Unable to reach backend: {0}")]
     UiDisconnected(String),
+    #[error("Backend is too busy to take more input, try again in a moment")]
+    Busy,
 }
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone)]
 pub enum Connection {
-    #[default]
-    Disconnected,
+    /// Inputs sent before the backend connected, in the order they were sent
+    Disconnected(VecDeque<Input>),
     Connected(mpsc::Sender<Input>),
 }
 
+impl Default for Connection {
+    fn default() -> Self {
+        Self::Disconnected(VecDeque::new())
+    }
+}
+
 impl Connection {
+    /// Sends `input` to the backend, or queues it until the backend has connected
     pub fn send(&mut self, input: Input) -> Result<(), Error> {
         match self {
-            Connection::Disconnected => todo!(),
-            Connection::Connected(connection) => connection
-                .try_send(input)
-                .map_err(|error| Error::UiDisconnected(error.to_string())),
+            Connection::Disconnected(queued) => {
+                tracing::debug!(?input, "Backend not connected yet, queueing input");
+                queued.push_back(input);
+                Ok(())
+            }
+            Connection::Connected(connection) => {
+                connection.try_send(input).map_err(|error| match error {
+                    mpsc::error::TrySendError::Full(_) => Error::Busy,
+                    mpsc::error::TrySendError::Closed(_) => {
+                        Error::UiDisconnected("Input connection to backend closed".to_string())
+                    }
+                })
+            }
         }
     }
+
+    /// Switches over to `sender`, passing on the inputs queued while disconnected
+    pub fn connect(&mut self, sender: mpsc::Sender<Input>) -> Result<(), Error> {
+        let queued = match std::mem::replace(self, Connection::Connected(sender)) {
+            Connection::Disconnected(queued) => queued,
+            Connection::Connected(_) => VecDeque::new(),
+        };
+        if !queued.is_empty() {
+            tracing::debug!(inputs = queued.len(), "Passing on queued inputs");
+        }
+        queued.into_iter().try_for_each(|input| self.send(input))
+    }
 }
 
 #[derive(Clone, Debug)]
 pub enum Input {
+    /// Stops the job currently running in the backend, if any
+    Cancel,
     Uninitialized(uninitialized::Input),
-    Goodreads(goodreads::Input),
+    Welcome(welcome::Input),
+    Home(home::Input),
 }
 
 #[derive(Debug, Clone)]
 pub enum Output {
+    /// The running job was stopped before finishing. The backend is left in the state it had before the job.
+    Cancelled,
     Connection(Connection),
-    Goodreads(goodreads::Output),
     Uninitialized(uninitialized::Output),
+    Welcome(welcome::Output),
+    Home(home::Output),
 }
 
 #[derive(Clone, Debug)]
 pub enum State {
     Uninitialized(Uninitialized),
-    Goodreads(goodreads::State),
+    Welcome(welcome::Welcome),
+    Home(home::Home),
 }
 
 impl Default for State {
@@ -64,18 +106,103 @@ impl Default for State {
     }
 }
 
+impl State {
+    /// Short name for logs, since the state itself may hold a lot of data
+    pub fn name(&self) -> &'static str {
+        match self {
+            State::Uninitialized(_) => "Uninitialized",
+            State::Welcome(_) => "Welcome",
+            State::Home(_) => "Home",
+        }
+    }
+}
+
+/// Sends updates on a running job to the UI, before the job has finished
+#[derive(Clone, Debug, Default)]
+pub struct Progress(Option<mpsc::UnboundedSender<Output>>);
+
+impl Progress {
+    pub fn channel() -> (Self, mpsc::UnboundedReceiver<Output>) {
+        let (sender, receiver) = mpsc::unbounded_channel();
+        (Self(Some(sender)), receiver)
+    }
+
+    pub fn report(&self, output: impl Into<Output>) {
+        if let Some(sender) = &self.0 {
+            // Nobody listening just means nobody is interested in the progress
+            let _ = sender.send(output.into());
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct Backend {
     browser_connection: Option<browser::Connection>,
     state: State,
+    progress: Progress,
 }
 
 impl Backend {
+    pub fn new(progress: Progress) -> Self {
+        diagnostics::set_backend_state(State::default().name());
+        Self {
+            progress,
+            ..Default::default()
+        }
+    }
+
+    /// Runs `input` until it finishes or a `Input::Cancel` arrives. Anything else arriving in the meantime is
+    /// queued in `pending`.
+    pub async fn run(
+        &mut self,
+        input: Input,
+        inputs: &mut mpsc::Receiver<Input>,
+        pending: &mut VecDeque<Input>,
+    ) -> Result<Option<Output>, Error> {
+        if let Input::Cancel = input {
+            // Nothing to cancel
+            return Ok(None);
+        }
+
+        // Dropping the job cancels it. The state is only replaced once a job finishes, and browser connections
+        // clean up after themselves when dropped.
+        let job = self.update(input);
+        tokio::pin!(job);
+        loop {
+            tokio::select! {
+                result = &mut job => return result,
+                input = inputs.recv() => match input {
+                    Some(Input::Cancel) => {
+                        tracing::info!("Job cancelled");
+                        return Ok(Some(Output::Cancelled));
+                    }
+                    Some(input) => pending.push_back(input),
+                    None => return Err(Error::UiDisconnected("Input connection from GUI closed".to_string())),
+                },
+            }
+        }
+    }
+
+    #[tracing::instrument(name = "backend", skip_all, fields(state = self.state.name()))]
     pub async fn update(&mut self, input: Input) -> Result<Option<Output>, Error> {
-        dbg!(self.state.clone());
-        dbg!(self.browser_connection.is_some());
-        dbg!(input.clone());
+        tracing::debug!(
+            ?input,
+            browser_connected = self.browser_connection.is_some(),
+            "Handling input"
+        );
+        let from = self.state.name();
+        let result = self.transition(input).await;
+        match &result {
+            Ok(_) => {
+                tracing::info!(from, to = self.state.name(), "State transition");
+                diagnostics::set_backend_state(self.state.name());
+            }
+            Err(error) => tracing::warn!("Input failed: {error}"),
+        }
+        result
+    }
 
+    async fn transition(&mut self, input: Input) -> Result<Option<Output>, Error> {
         if let State::Uninitialized(state) = self.state.clone()
             && let Input::Uninitialized(input) = input
         {
@@ -91,11 +218,15 @@ impl Backend {
             .map_err(|error| uninitialized::Error::BrowserConnection(error.to_string()))?;
 
         let (state, output) = match self.state.clone() {
-            State::Goodreads(state) => {
+            State::Welcome(state) => {
+                let (state, output) = self.sign_in(state, input.try_into()?).await?;
+                (state, Ok(output.map(|output| output.into())))
+            }
+            State::Home(state) => {
                 let (state, output) = state
-                    .update(&mut connection.browser, input.try_into()?)
+                    .update(&connection.session(), input.try_into()?)
                     .await?;
-                (state, Ok(output))
+                (state, Ok(output.map(|output| output.into())))
             }
             _ => (
                 self.state.clone(),
@@ -109,4 +240,96 @@ impl Backend {
         self.state = state;
         output
     }
+
+    /// Passes `input` on to `state`. Should signing in need the user's help in a hidden browser, a visible browser
+    /// is launched in its place, and signing in starts over in there.
+    async fn sign_in(
+        &mut self,
+        state: welcome::Welcome,
+        input: welcome::Input,
+    ) -> Result<(State, Option<welcome::Output>), Error> {
+        let connection = self
+            .browser_connection
+            .as_ref()
+            .context("Browser disconnected!")
+            .map_err(|error| uninitialized::Error::BrowserConnection(error.to_string()))?;
+        let result = state
+            .clone()
+            .update(&connection.session(), input.clone(), &self.progress)
+            .await;
+        let Err(welcome::Error::Source(crate::source::Error::NeedsVisibleBrowser)) = result else {
+            return Ok(result?);
+        };
+
+        tracing::info!("Relaunching the browser visibly, so the user can sign in");
+        let config = browser::DriverConfig {
+            headless: false,
+            ..connection.config().clone()
+        };
+        uninitialized::connect(&mut self.browser_connection, &config).await?;
+        let connection = self
+            .browser_connection
+            .as_ref()
+            .context("Browser disconnected!")
+            .map_err(|error| uninitialized::Error::BrowserConnection(error.to_string()))?;
+
+        // The new browser knows nothing of the previous attempt, so an answered challenge can't be resumed
+        let (welcome::Input::LoginAttempt { credentials }
+        | welcome::Input::ChallengeAnswer { credentials, .. }) = input;
+        Ok(state
+            .update(
+                &connection.session(),
+                welcome::Input::LoginAttempt { credentials },
+                &self.progress,
+            )
+            .await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply_order(id: &str) -> Input {
+        home::Input::ApplyOrder(vec![id.to_string()]).into()
+    }
+
+    fn order_id(input: Input) -> String {
+        match input {
+            Input::Home(home::Input::ApplyOrder(ids)) => ids.concat(),
+            input => panic!("Unexpected input {input:?}"),
+        }
+    }
+
+    #[test]
+    fn inputs_queued_while_disconnected_arrive_in_order() {
+        let mut connection = Connection::default();
+        connection.send(apply_order("first")).unwrap();
+        connection.send(apply_order("second")).unwrap();
+
+        let (sender, mut receiver) = mpsc::channel(8);
+        connection.connect(sender).unwrap();
+        connection.send(apply_order("third")).unwrap();
+
+        let received: Vec<_> = std::iter::from_fn(|| receiver.try_recv().ok())
+            .map(order_id)
+            .collect();
+        assert_eq!(received, ["first", "second", "third"]);
+    }
+
+    #[test]
+    fn full_and_closed_connections_are_reported() {
+        let (sender, receiver) = mpsc::channel(1);
+        let mut connection = Connection::default();
+        connection.connect(sender).unwrap();
+
+        connection.send(Input::Cancel).unwrap();
+        assert!(matches!(connection.send(Input::Cancel), Err(Error::Busy)));
+
+        drop(receiver);
+        assert!(matches!(
+            connection.send(Input::Cancel),
+            Err(Error::UiDisconnected(_))
+        ));
+    }
 }
@@ -1,5 +0,0 @@
-#[derive(Clone, Debug)]
-pub struct BookInfo {
-    pub title: String,
-    pub url: url::Url,
-}
@@ -0,0 +1,104 @@
+use crate::{
+    backend::{self, State},
+    common::webdriver::Session,
+    source::{self, Item, Source},
+};
+
+use color_eyre::{Result, eyre::eyre};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid message ({message}) for state {state}")]
+    InvalidState { state: String, message: String },
+    #[error(transparent)]
+    Source(#[from] source::Error),
+}
+
+#[derive(Clone, Debug)]
+pub enum Input {
+    /// Writes a new order back to the list, as item IDs with the first item first
+    ApplyOrder(Vec<String>),
+}
+
+impl From<Input> for backend::Input {
+    fn from(input: Input) -> Self {
+        Self::Home(input)
+    }
+}
+
+impl TryFrom<backend::Input> for Input {
+    type Error = Error;
+
+    fn try_from(input: backend::Input) -> Result<Self, Self::Error> {
+        match input {
+            backend::Input::Home(input) => Ok(input),
+            _ => Err(Self::Error::InvalidState {
+                state: "Home".into(),
+                message: format!("{:?}", input),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Output {
+    OrderApplied,
+}
+
+impl From<Output> for backend::Output {
+    fn from(output: Output) -> Self {
+        Self::Home(output)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Home {
+    source: &'static dyn Source,
+    user_id: String,
+    items: Vec<Item>,
+}
+
+impl From<Home> for State {
+    fn from(state: Home) -> Self {
+        Self::Home(state)
+    }
+}
+
+impl Home {
+    pub fn new(source: &'static dyn Source, user_id: String, items: Vec<Item>) -> Self {
+        Self {
+            source,
+            user_id,
+            items,
+        }
+    }
+
+    pub async fn update(
+        mut self,
+        session: &Session,
+        input: Input,
+    ) -> Result<(State, Option<Output>), Error> {
+        match input {
+            Input::ApplyOrder(order) => {
+                let items = order
+                    .iter()
+                    .map(|id| {
+                        self.items
+                            .iter()
+                            .find(|item| &item.id == id)
+                            .cloned()
+                            .ok_or_else(|| eyre!("Unknown item: {id}"))
+                    })
+                    .collect::<Result<Vec<_>>>()
+                    .map_err(source::Error::from)?;
+
+                self.source
+                    .apply_order(session, &self.user_id, &items)
+                    .await?;
+                tracing::info!(items = items.len(), "List reordered");
+                self.items = items;
+                Ok((self.into(), Some(Output::OrderApplied)))
+            }
+        }
+    }
+}
@@ -2,14 +2,15 @@ use color_eyre::Result;
 
 use crate::{
     backend::{self, State},
-    common::{browser, helpers::Mode},
+    common::{browser, config::Config},
+    source::{SignInMethod, Source},
 };
 
 #[derive(thiserror::Error, Debug, Clone)]
 pub enum Error {
-    #[error("failed to connect to browser")]
+    #[error("Failed to connect to browser: {0}")]
     BrowserConnection(String),
-    #[error("")]
+    #[error("{0}")]
     Other(String),
 }
 
@@ -23,7 +24,8 @@ impl From<Error> for backend::Error {
 pub enum Input {
     Launch {
         browser_driver_config: browser::DriverConfig,
-        mode: Mode,
+        config: Box<Config>,
+        source: &'static dyn Source,
     },
 }
 
@@ -49,7 +51,7 @@ impl TryFrom<backend::Input> for Input {
 
 #[derive(Clone, Debug)]
 pub enum Output {
-    Initialized(Mode),
+    Initialized(&'static dyn Source),
 }
 
 impl From<Output> for backend::Output {
@@ -67,6 +69,27 @@ impl From<Uninitialized> for State {
 #[derive(Clone, Debug, Default)]
 pub struct Uninitialized {}
 
+/// Makes `connection` a browser launched with `config`. A browser launched with a different config is closed first.
+pub async fn connect(
+    connection: &mut Option<browser::Connection>,
+    config: &browser::DriverConfig,
+) -> Result<(), Error> {
+    if connection
+        .as_ref()
+        .is_some_and(|connection| connection.config() == config)
+    {
+        return Ok(());
+    }
+
+    // Dropping the old connection also stops its driver, if we launched it, so the new one can take its place
+    *connection = None;
+    match browser::Connection::new(config).await {
+        Ok(new_connection) => *connection = Some(new_connection),
+        Err(error) => return Err(Error::BrowserConnection(error.to_string())),
+    }
+    Ok(())
+}
+
 impl Uninitialized {
     pub async fn update(
         self,
@@ -75,23 +98,20 @@ impl Uninitialized {
     ) -> Result<(State, Option<Output>), Error> {
         match input {
             Input::Launch {
-                browser_driver_config,
-                mode,
+                mut browser_driver_config,
+                config,
+                source,
             } => {
-                if connection.is_none() {
-                    match browser::Connection::new(&browser_driver_config).await {
-                        Ok(new_connection) => *connection = Some(new_connection),
-                        Err(error) => return Err(Error::BrowserConnection(error.to_string())),
-                    }
+                // These sources have the user sign in in the browser window, so it can't be hidden
+                if source.sign_in_method() == SignInMethod::Browser {
+                    browser_driver_config.headless = false;
                 }
+                connect(connection, &browser_driver_config).await?;
 
-                match mode {
-                    Mode::Goodreads => Ok((
-                        State::Goodreads(backend::goodreads::welcome::Welcome::default().into()),
-                        Some(Output::Initialized(mode.into()).into()),
-                    )),
-                    Mode::Steam => todo!(),
-                }
+                Ok((
+                    backend::welcome::Welcome::new(source, *config).into(),
+                    Some(Output::Initialized(source)),
+                ))
             }
         }
     }
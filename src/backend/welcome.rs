@@ -0,0 +1,131 @@
+use crate::{
+    backend::{self, State},
+    common::{
+        config::Config,
+        helpers::{Credentials, Secret},
+        webdriver::Session,
+    },
+    source::{self, Challenge, Item, SignIn, Source},
+};
+
+use color_eyre::Result;
+
+use super::home::Home;
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Invalid message ({message}) for state {state}")]
+    InvalidState { state: String, message: String },
+    #[error(transparent)]
+    Source(#[from] source::Error),
+}
+
+#[derive(Clone, Debug)]
+pub enum Input {
+    /// Signs in and fetches the list of the user. Sources signing in through the browser ignore the credentials.
+    LoginAttempt { credentials: Credentials },
+    /// Answers the challenge from `Output::ChallengeRequired`, then carries on like `LoginAttempt`
+    ChallengeAnswer {
+        credentials: Credentials,
+        answer: Secret,
+    },
+}
+
+impl From<Input> for backend::Input {
+    fn from(input: Input) -> Self {
+        Self::Welcome(input)
+    }
+}
+
+impl TryFrom<backend::Input> for Input {
+    type Error = Error;
+
+    fn try_from(input: backend::Input) -> Result<Self, Self::Error> {
+        match input {
+            backend::Input::Welcome(input) => Ok(input),
+            _ => Err(Self::Error::InvalidState {
+                state: "Welcome".into(),
+                message: format!("{:?}", input),
+            }),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Output {
+    /// Signing in is paused until the challenge is answered with `Input::ChallengeAnswer`. For
+    /// `Challenge::InBrowser`, this is sent as progress instead, while waiting for the user.
+    ChallengeRequired(Challenge),
+    /// Sent while loading the list after signing in
+    ItemsFetched {
+        fetched: usize,
+        total: usize,
+    },
+    LoginSuccess {
+        items: Vec<Item>,
+    },
+}
+
+impl From<Output> for backend::Output {
+    fn from(output: Output) -> Self {
+        Self::Welcome(output)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Welcome {
+    source: &'static dyn Source,
+    config: Config,
+}
+
+impl From<Welcome> for State {
+    fn from(state: Welcome) -> Self {
+        Self::Welcome(state)
+    }
+}
+
+impl Welcome {
+    pub fn new(source: &'static dyn Source, config: Config) -> Self {
+        Self { source, config }
+    }
+
+    pub async fn update(
+        self,
+        session: &Session,
+        input: Input,
+        progress: &backend::Progress,
+    ) -> Result<(State, Option<Output>), Error> {
+        let signed_in = match &input {
+            Input::LoginAttempt { credentials } => {
+                self.source
+                    .login(session, credentials, None, progress)
+                    .await
+            }
+            Input::ChallengeAnswer {
+                credentials,
+                answer,
+            } => {
+                self.source
+                    .login(session, credentials, Some(answer), progress)
+                    .await
+            }
+        }?;
+
+        let user_id = match signed_in {
+            SignIn::Done { user_id } => user_id,
+            SignIn::Challenge(challenge) => {
+                tracing::info!(?challenge, "Sign in needs an answer to a challenge");
+                return Ok((self.into(), Some(Output::ChallengeRequired(challenge))));
+            }
+        };
+
+        tracing::info!(source = self.source.name(), user_id, "Signed in");
+        let items = self
+            .source
+            .fetch_items(&user_id, &self.config, progress)
+            .await?;
+
+        let state = Home::new(self.source, user_id, items.clone());
+        Ok((state.into(), Some(Output::LoginSuccess { items })))
+    }
+}
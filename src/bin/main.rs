@@ -1,6 +1,7 @@
 use godric::{
     Message,
     backend::{self, Connection},
+    common::{config::Config, logging},
     scene::{self, Scene},
 };
 
@@ -10,29 +11,38 @@ use tokio::sync::mpsc;
 
 pub fn main() -> Result<()> {
     color_eyre::install()?;
-    dotenv::dotenv()?;
+    let config = Config::load();
+    let level = config
+        .as_ref()
+        .map(|config| config.log_level.clone())
+        .unwrap_or_else(|_| Config::default().log_level);
+    let _log_guard = logging::init(&level);
+
+    // Credentials in .env are only a fallback, so it's fine not to have one
+    if let Err(error) = dotenv::dotenv() {
+        tracing::debug!("No .env file loaded: {error}");
+    }
+
     Ok(iced::application("Godric", Godric::update, Godric::view)
         .theme(Godric::theme)
-        .subscription(Godric::backend_subscription)
+        .subscription(Godric::subscription)
         .window(iced::window::Settings {
             icon: iced::window::icon::from_file("Assets/Logo/Icon - zoomed.jpg").ok(),
             ..Default::default()
         })
-        .run_with(|| (Godric::default(), Task::none()))?)
+        .run_with(|| (Godric::new(config), Task::none()))?)
 }
 
 struct Godric {
     backend: Connection,
     scene: Scene,
-    theme: iced::Theme,
 }
 
-impl Default for Godric {
-    fn default() -> Self {
+impl Godric {
+    fn new(config: Result<Config>) -> Self {
         Self {
             backend: Default::default(),
-            scene: Default::default(),
-            theme: iced::Theme::SolarizedLight,
+            scene: Scene::new(config),
         }
     }
 }
@@ -40,13 +50,12 @@ impl Default for Godric {
 impl Godric {
     fn update(&mut self, message: Message) -> Task<Message> {
         // Special treatment for establishing initial backend connection
-        if let Message::Backend(ref message) = message {
-            if let Ok(message) = message {
-                if let backend::Output::Connection(connection) = message {
-                    self.backend = connection.clone();
-                }
+        let connection_error = match &message {
+            Message::Backend(Ok(backend::Output::Connection(Connection::Connected(sender)))) => {
+                self.backend.connect(sender.clone()).err()
             }
-        }
+            _ => None,
+        };
 
         let message = match message {
             Message::Scene(message) => Ok(message),
@@ -54,10 +63,18 @@ impl Godric {
         };
 
         let (input, task) = self.scene.update(message);
-        if let Some(input) = input {
-            self.backend.send(input);
+        let send_error = input.and_then(|input| self.backend.send(input).err());
+        match connection_error.or(send_error) {
+            Some(error) => Task::batch([task, self.show_error(error)]),
+            None => task,
         }
+    }
 
+    /// Hands an error about reaching the backend to the scene, so it's shown instead of getting lost
+    fn show_error(&mut self, error: backend::Error) -> Task<Message> {
+        tracing::error!("Unable to send input to backend: {error}");
+        // Scenes don't send input in response to errors
+        let (_, task) = self.scene.update(Err(error));
         task
     }
 
@@ -71,7 +88,14 @@ impl Godric {
     }
 
     fn theme(&self) -> iced::Theme {
-        self.theme.clone()
+        self.scene.theme()
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        Subscription::batch([
+            self.backend_subscription(),
+            self.scene.subscription().map(Message::Scene),
+        ])
     }
 
     fn backend_subscription(&self) -> Subscription<Message> {
@@ -79,7 +103,9 @@ impl Godric {
             iced::stream::channel(0, |mut ui| async move {
                 // Executed only once, even on repeated calls of subscription
                 let (sender, mut receiver) = mpsc::channel(50);
-                let mut backend = crate::backend::Backend::default();
+                let (progress, mut progress_receiver) = crate::backend::Progress::channel();
+                let mut backend = crate::backend::Backend::new(progress);
+                let mut pending = std::collections::VecDeque::new();
 
                 ui.send(Ok(crate::backend::Output::Connection(
                     Connection::Connected(sender),
@@ -89,12 +115,31 @@ impl Godric {
 
                 // Executed continuously, kept alive across calls
                 loop {
-                    let message = receiver
-                        .recv()
-                        .await
-                        .expect("Input connection from GUI closed!");
+                    let message = match pending.pop_front() {
+                        Some(message) => message,
+                        None => receiver
+                            .recv()
+                            .await
+                            .expect("Input connection from GUI closed!"),
+                    };
+
+                    // Pass on progress of the job while waiting for it to finish
+                    let job = backend.run(message, &mut receiver, &mut pending);
+                    tokio::pin!(job);
+                    let result = loop {
+                        tokio::select! {
+                            result = &mut job => break result,
+                            Some(progress) = progress_receiver.recv() => {
+                                // The UI going away is noticed when the job result is sent
+                                let _ = ui.send(Ok(progress)).await;
+                            }
+                        }
+                    };
+                    while let Ok(progress) = progress_receiver.try_recv() {
+                        let _ = ui.send(Ok(progress)).await;
+                    }
 
-                    match backend.update(message).await {
+                    match result {
                         Ok(message) => {
                             if let Some(message) = message {
                                 ui.send(Ok(message)).await;
@@ -1,8 +1,21 @@
-use color_eyre::Result;
+use std::path::PathBuf;
+
+use color_eyre::{Result, eyre::Context};
 use thirtyfour as tf;
 
+use crate::common::{diagnostics, webdriver};
+
 #[derive(
-    Copy, Clone, Debug, strum_macros::Display, strum_macros::EnumString, strum_macros::EnumIter,
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
 )]
 pub enum Browser {
     Chrome,
@@ -35,16 +48,50 @@ impl Browser {
     }
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct DriverConfig {
     pub browser: Browser,
     pub driver_address: std::net::SocketAddrV4,
     pub headless: bool,
+    #[serde(default)]
+    pub waits: webdriver::Waits,
 }
 
+impl Default for DriverConfig {
+    fn default() -> Self {
+        Self {
+            browser: Browser::Firefox,
+            driver_address: std::net::SocketAddrV4::new(
+                std::net::Ipv4Addr::new(127, 0, 0, 1),
+                4444,
+            ),
+            headless: true,
+            waits: Default::default(),
+        }
+    }
+}
+
+// Fields are dropped in order, so the browser session is closed while the driver is still around
 pub struct Connection {
     pub browser: tf::WebDriver,
-    driver: Option<std::process::Child>,
+    /// What the browser was launched with, to tell when a different one is needed
+    config: DriverConfig,
+    driver: Option<Driver>,
+}
+
+/// Driver process launched by us. It is killed when dropped, so that cancelled or failed launches don't leave it
+/// running.
+#[derive(Debug)]
+struct Driver(std::process::Child);
+
+impl Drop for Driver {
+    fn drop(&mut self) {
+        tracing::info!(pid = self.0.id(), "Stopping driver");
+        diagnostics::set_driver_status(diagnostics::DriverStatus::Stopped);
+        // The process may have exited on its own already
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
 }
 
 impl std::fmt::Debug for Connection {
@@ -56,7 +103,78 @@ impl std::fmt::Debug for Connection {
     }
 }
 
+/// Span for a single WebDriver command, so that logs show which step of a flow was running
+pub fn command_span(command: &'static str) -> tracing::Span {
+    tracing::debug_span!("webdriver", command)
+}
+
+/// Older captures are deleted when a new one is saved
+const KEPT_CAPTURES: usize = 10;
+
+/// Where pages are saved when a step fails, e.g. `~/.local/share/godric/debug` on Linux
+pub fn debug_directory() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("godric")
+        .join("debug")
+}
+
+/// Saves a screenshot and the HTML of the page currently shown to a new, timestamped folder in
+/// `debug_directory()`, returning the folder. Meant for figuring out why a step failed, e.g. a changed selector or a
+/// CAPTCHA.
+pub async fn capture_page(browser: &tf::WebDriver) -> Result<PathBuf> {
+    let folder =
+        debug_directory().join(chrono::Local::now().format("%Y-%m-%d_%H-%M-%S").to_string());
+    std::fs::create_dir_all(&folder)
+        .with_context(|| format!("Failed to create {}", folder.display()))?;
+    if let Err(error) = prune_captures() {
+        tracing::warn!("Failed to delete old captures: {error:?}");
+    }
+
+    // Save whatever we can get, as the browser may be in a bad state
+    let screenshot = browser
+        .screenshot(&folder.join("screenshot.png"))
+        .await
+        .context("Failed to save screenshot");
+    let source = match browser.source().await {
+        Ok(source) => {
+            std::fs::write(folder.join("page.html"), source).context("Failed to save page source")
+        }
+        Err(error) => Err(error).context("Failed to get page source"),
+    };
+    if let Ok(url) = browser.current_url().await {
+        tracing::info!(%url, folder = %folder.display(), "Captured page");
+    }
+
+    match (screenshot, source) {
+        (Err(error), Err(_)) => Err(error),
+        _ => Ok(folder),
+    }
+}
+
+/// Deletes all but the newest `KEPT_CAPTURES` folders in `debug_directory()`
+fn prune_captures() -> Result<()> {
+    let directory = debug_directory();
+    let mut captures = std::fs::read_dir(&directory)
+        .with_context(|| format!("Failed to read {}", directory.display()))?
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.file_type().is_ok_and(|kind| kind.is_dir()))
+        .map(|entry| entry.path())
+        .collect::<Vec<_>>();
+
+    // Folders are named by their timestamp, so they sort from oldest to newest
+    captures.sort();
+    let excess = captures.len().saturating_sub(KEPT_CAPTURES);
+    for folder in &captures[..excess] {
+        std::fs::remove_dir_all(folder)
+            .with_context(|| format!("Failed to delete {}", folder.display()))?;
+    }
+
+    Ok(())
+}
+
 impl Connection {
+    #[tracing::instrument(name = "browser_launch", skip_all, fields(browser = %config.browser, address = %config.driver_address))]
     pub async fn new(config: &DriverConfig) -> Result<Self> {
         // Don't attempt to launch the driver, if a corresponding process already exists
         let driver = (sysinfo::System::processes_by_exact_name(
@@ -70,24 +188,50 @@ impl Connection {
             &config.driver_address,
         )?);
 
+        tracing::info!(driver_launched = driver.is_some(), "Driver ready");
+        diagnostics::set_driver_status(match &driver {
+            Some(driver) => diagnostics::DriverStatus::Launched {
+                pid: driver.0.id(),
+                running: true,
+            },
+            None => diagnostics::DriverStatus::External,
+        });
+
         let browser = Self::launch_browser(config).await?;
+        tracing::info!("Browser session started");
+
+        Ok(Self {
+            browser,
+            config: config.clone(),
+            driver,
+        })
+    }
+
+    pub fn config(&self) -> &DriverConfig {
+        &self.config
+    }
 
-        Ok(Self { browser, driver })
+    /// Session for driving the browser step by step
+    pub fn session(&self) -> webdriver::Session {
+        webdriver::Session::new(
+            self.browser.clone(),
+            self.config.waits,
+            self.config.headless,
+        )
     }
 
-    fn launch_driver(
-        browser: &Browser,
-        address: &std::net::SocketAddrV4,
-    ) -> Result<std::process::Child> {
-        Ok(std::process::Command::new(browser.driver())
-            .args([
-                "--host",
-                &address.ip().to_string(),
-                "--port",
-                &address.port().to_string(),
-            ])
-            .stdout(std::process::Stdio::null())
-            .spawn()?)
+    fn launch_driver(browser: &Browser, address: &std::net::SocketAddrV4) -> Result<Driver> {
+        Ok(Driver(
+            std::process::Command::new(browser.driver())
+                .args([
+                    "--host",
+                    &address.ip().to_string(),
+                    "--port",
+                    &address.port().to_string(),
+                ])
+                .stdout(std::process::Stdio::null())
+                .spawn()?,
+        ))
     }
 
     async fn launch_browser(config: &DriverConfig) -> Result<tf::WebDriver> {
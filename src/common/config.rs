@@ -0,0 +1,94 @@
+use std::path::PathBuf;
+
+use color_eyre::{
+    Result,
+    eyre::{Context, ContextCompat},
+};
+
+use crate::common::{
+    browser, http,
+    keybindings::KeyBindings,
+    sorting::{Direction, Rule, SortKey},
+};
+
+/// Everything the user can change in the settings, as stored in `config.toml` in the config directory
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Config {
+    pub browser: browser::DriverConfig,
+    /// Name of one of the built-in themes of iced
+    pub theme: String,
+    pub requests: http::SchedulerConfig,
+    /// Where downloaded covers are kept, so they don't have to be downloaded again
+    pub cache_dir: PathBuf,
+    /// Shelf to sort, e.g. "to-read"
+    pub default_shelf: String,
+    /// Two letter code of the country whose Steam store prices are shown, e.g. "US"
+    pub steam_country: String,
+    /// Automatic sorting applied to a list once its items have been fetched for the first time
+    pub default_sorting: Rule,
+    pub key_bindings: KeyBindings,
+    /// One of `logging::LEVELS`. Only read at startup.
+    pub log_level: String,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            browser: Default::default(),
+            theme: iced::Theme::SolarizedLight.to_string(),
+            requests: Default::default(),
+            cache_dir: dirs::cache_dir()
+                .unwrap_or_else(std::env::temp_dir)
+                .join("godric")
+                .join("covers"),
+            default_shelf: "to-read".to_string(),
+            steam_country: "US".to_string(),
+            default_sorting: Rule::new(SortKey::DateAdded, Direction::Ascending),
+            key_bindings: Default::default(),
+            log_level: "info".to_string(),
+        }
+    }
+}
+
+impl Config {
+    /// Location of the config file, e.g. `~/.config/godric/config.toml` on Linux
+    pub fn path() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("godric").join("config.toml"))
+    }
+
+    /// Loads the config file, falling back to the defaults if there is none yet
+    pub fn load() -> Result<Self> {
+        let path = Self::path().context("Unable to locate config directory")?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let config = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read config file {}", path.display()))?;
+        toml::from_str(&config)
+            .with_context(|| format!("Failed to parse config file {}", path.display()))
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::path().context("Unable to locate config directory")?;
+        if let Some(directory) = path.parent() {
+            std::fs::create_dir_all(directory).with_context(|| {
+                format!("Failed to create config directory {}", directory.display())
+            })?;
+        }
+
+        let config = toml::to_string_pretty(self).context("Failed to serialize config")?;
+        std::fs::write(&path, config)
+            .with_context(|| format!("Failed to write config file {}", path.display()))
+    }
+
+    /// The configured theme, or the default one if the name is unknown
+    pub fn theme(&self) -> iced::Theme {
+        iced::Theme::ALL
+            .iter()
+            .find(|theme| theme.to_string() == self.theme)
+            .cloned()
+            .unwrap_or(iced::Theme::SolarizedLight)
+    }
+}
@@ -0,0 +1,182 @@
+use std::path::PathBuf;
+
+use chacha20poly1305::{
+    ChaCha20Poly1305, Key, KeyInit, Nonce,
+    aead::{Aead, AeadCore, OsRng},
+};
+use color_eyre::{
+    Result,
+    eyre::{Context, ContextCompat, eyre},
+};
+use rand::RngCore;
+use zeroize::Zeroizing;
+
+use crate::common::helpers::Credentials;
+
+/// Marks the file format, so that older or foreign files are rejected instead of failing to decrypt
+const MAGIC: &[u8] = b"godric-credentials-1";
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+
+/// Credentials saved in a local file, encrypted with a key derived from a master passphrase
+#[derive(Clone, Debug)]
+pub struct CredentialStore {
+    path: PathBuf,
+}
+
+impl CredentialStore {
+    pub fn new(path: PathBuf) -> Self {
+        Self { path }
+    }
+
+    /// The store in the data directory, e.g. `~/.local/share/godric/credentials` on Linux
+    pub fn default_location() -> Option<Self> {
+        Some(Self::new(
+            dirs::data_dir()?.join("godric").join("credentials"),
+        ))
+    }
+
+    pub fn exists(&self) -> bool {
+        self.path.exists()
+    }
+
+    pub fn save(&self, credentials: &Credentials, passphrase: &str) -> Result<()> {
+        let mut salt = [0; SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let cipher = cipher(passphrase, &salt)?;
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+
+        let plaintext = Zeroizing::new(
+            toml::to_string(credentials).context("Failed to serialize credentials")?,
+        );
+        let ciphertext = cipher
+            .encrypt(&nonce, plaintext.as_bytes())
+            .map_err(|_| eyre!("Failed to encrypt credentials"))?;
+
+        let contents = [MAGIC, &salt, &nonce, &ciphertext].concat();
+        if let Some(directory) = self.path.parent() {
+            std::fs::create_dir_all(directory)
+                .with_context(|| format!("Failed to create directory {}", directory.display()))?;
+        }
+        write_private(&self.path, &contents)
+            .with_context(|| format!("Failed to write {}", self.path.display()))
+    }
+
+    pub fn load(&self, passphrase: &str) -> Result<Credentials> {
+        let contents = std::fs::read(&self.path)
+            .with_context(|| format!("Failed to read {}", self.path.display()))?;
+        let contents = contents
+            .strip_prefix(MAGIC)
+            .context("Not a credential store, or one from an incompatible version")?;
+        if contents.len() < SALT_LENGTH + NONCE_LENGTH {
+            return Err(eyre!("Credential store is truncated"));
+        }
+        let (salt, contents) = contents.split_at(SALT_LENGTH);
+        let (nonce, ciphertext) = contents.split_at(NONCE_LENGTH);
+
+        let plaintext = Zeroizing::new(
+            cipher(passphrase, salt)?
+                .decrypt(Nonce::from_slice(nonce), ciphertext)
+                .map_err(|_| eyre!("Wrong passphrase, or the credential store is damaged"))?,
+        );
+        let plaintext =
+            std::str::from_utf8(&plaintext).context("Credential store contents are damaged")?;
+        toml::from_str(plaintext).context("Credential store contents are damaged")
+    }
+
+    /// Deletes the store, if there is one
+    pub fn forget(&self) -> Result<()> {
+        match std::fs::remove_file(&self.path) {
+            Err(error) if error.kind() != std::io::ErrorKind::NotFound => {
+                Err(error).with_context(|| format!("Failed to delete {}", self.path.display()))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Cipher keyed with a key stretched from the passphrase, so that guessing passphrases is slow
+fn cipher(passphrase: &str, salt: &[u8]) -> Result<ChaCha20Poly1305> {
+    let mut key = Zeroizing::new([0u8; 32]);
+    argon2::Argon2::default()
+        .hash_password_into(passphrase.as_bytes(), salt, key.as_mut_slice())
+        .map_err(|error| eyre!("Failed to derive key from passphrase: {error}"))?;
+    Ok(ChaCha20Poly1305::new(Key::from_slice(key.as_slice())))
+}
+
+/// Writes a file only readable by the current user, where the platform supports it
+fn write_private(path: &std::path::Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PASSPHRASE: &str = "correct horse battery staple";
+
+    /// Store in its own file for each test, so that tests can run in parallel
+    fn store(name: &str) -> CredentialStore {
+        let path = std::env::temp_dir()
+            .join(format!("godric-test-{}", std::process::id()))
+            .join(name);
+        let store = CredentialStore::new(path);
+        store.forget().unwrap();
+        store
+    }
+
+    fn credentials() -> Credentials {
+        Credentials {
+            email: "godric@example.com".to_string(),
+            password: "Swordfish".to_string().into(),
+        }
+    }
+
+    #[test]
+    fn saved_credentials_load_again() {
+        let store = store("round_trip");
+        store.save(&credentials(), PASSPHRASE).unwrap();
+        let loaded = store.load(PASSPHRASE).unwrap();
+        store.forget().unwrap();
+
+        assert_eq!(loaded.email, credentials().email);
+        assert_eq!(loaded.password.expose(), credentials().password.expose());
+    }
+
+    #[test]
+    fn wrong_passphrase_is_rejected() {
+        let store = store("wrong_passphrase");
+        store.save(&credentials(), PASSPHRASE).unwrap();
+        let loaded = store.load("incorrect horse battery staple");
+        store.forget().unwrap();
+
+        assert!(loaded.is_err());
+    }
+
+    #[test]
+    fn foreign_and_truncated_files_are_rejected() {
+        let store = store("damaged");
+        store.save(&credentials(), PASSPHRASE).unwrap();
+        let contents = std::fs::read(&store.path).unwrap();
+
+        let mut foreign = contents.clone();
+        foreign[0] ^= 0xff;
+        let damaged = [
+            foreign,
+            MAGIC.to_vec(),
+            contents[..MAGIC.len() + SALT_LENGTH].to_vec(),
+            contents[..contents.len() - 1].to_vec(),
+        ];
+        for contents in damaged {
+            std::fs::write(&store.path, contents).unwrap();
+            assert!(store.load(PASSPHRASE).is_err());
+        }
+        store.forget().unwrap();
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::VecDeque,
+    fmt::Write,
+    sync::{LazyLock, Mutex},
+    time::Duration,
+};
+
+use chrono::{DateTime, Local};
+
+/// Number of log lines kept for the diagnostics panel
+const LOG_CAPACITY: usize = 1000;
+/// Number of HTTP requests kept for the diagnostics panel
+const REQUEST_CAPACITY: usize = 50;
+
+static RECORDER: LazyLock<Mutex<Snapshot>> = LazyLock::new(Default::default);
+
+fn recorder() -> std::sync::MutexGuard<'static, Snapshot> {
+    // A panic while holding the lock leaves nothing half-written that we care about
+    RECORDER
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// What the app was doing recently, as shown in the diagnostics panel
+#[derive(Clone, Debug, Default)]
+pub struct Snapshot {
+    pub log: VecDeque<LogLine>,
+    pub requests: VecDeque<Request>,
+    pub backend_state: Option<&'static str>,
+    pub driver: DriverStatus,
+}
+
+#[derive(Clone, Debug)]
+pub struct LogLine {
+    pub time: DateTime<Local>,
+    pub level: tracing::Level,
+    /// Spans the event happened in, outermost first, e.g. "backend:http"
+    pub spans: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for LogLine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {:>5} ", self.time.format("%H:%M:%S%.3f"), self.level)?;
+        if !self.spans.is_empty() {
+            write!(f, "{}: ", self.spans)?;
+        }
+        write!(f, "{}", self.message)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub time: DateTime<Local>,
+    pub url: String,
+    /// The status code, or what went wrong if there was no response at all
+    pub status: Result<u16, String>,
+    pub elapsed: Duration,
+}
+
+impl std::fmt::Display for Request {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match &self.status {
+            Ok(status) => status.to_string(),
+            Err(error) => format!("failed ({error})"),
+        };
+        write!(
+            f,
+            "{} {} {} ({} ms)",
+            self.time.format("%H:%M:%S%.3f"),
+            status,
+            self.url,
+            self.elapsed.as_millis()
+        )
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub enum DriverStatus {
+    #[default]
+    NotStarted,
+    /// A driver was already running, so we connected to that one
+    External,
+    Launched {
+        pid: u32,
+        /// Checked whenever a snapshot is taken
+        running: bool,
+    },
+    Stopped,
+}
+
+impl std::fmt::Display for DriverStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DriverStatus::NotStarted => write!(f, "Not started"),
+            DriverStatus::External => write!(f, "Using driver that was already running"),
+            DriverStatus::Launched { pid, running: true } => {
+                write!(f, "Launched (pid {pid}), running")
+            }
+            DriverStatus::Launched {
+                pid,
+                running: false,
+            } => write!(f, "Launched (pid {pid}), exited unexpectedly"),
+            DriverStatus::Stopped => write!(f, "Stopped"),
+        }
+    }
+}
+
+pub fn snapshot() -> Snapshot {
+    let mut snapshot = recorder().clone();
+    if let DriverStatus::Launched { pid, running } = &mut snapshot.driver {
+        let pid = sysinfo::Pid::from_u32(*pid);
+        let mut system = sysinfo::System::new();
+        system.refresh_processes(sysinfo::ProcessesToUpdate::Some(&[pid]));
+        *running = system.process(pid).is_some();
+    }
+    snapshot
+}
+
+pub fn record_request(url: &reqwest::Url, status: Result<u16, String>, elapsed: Duration) {
+    push(
+        &mut recorder().requests,
+        Request {
+            time: Local::now(),
+            url: url.to_string(),
+            status,
+            elapsed,
+        },
+        REQUEST_CAPACITY,
+    );
+}
+
+pub fn set_backend_state(state: &'static str) {
+    recorder().backend_state = Some(state);
+}
+
+pub fn set_driver_status(status: DriverStatus) {
+    recorder().driver = status;
+}
+
+/// Plain text report of everything in the snapshot, for pasting into bug reports
+pub fn bundle(snapshot: &Snapshot, scene_state: &str, config: &super::config::Config) -> String {
+    let mut bundle = String::new();
+    let _ = writeln!(bundle, "Godric {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(
+        bundle,
+        "OS: {} {}",
+        std::env::consts::OS,
+        std::env::consts::ARCH
+    );
+    let _ = writeln!(bundle, "Scene: {scene_state}");
+    let _ = writeln!(
+        bundle,
+        "Backend: {}",
+        snapshot.backend_state.unwrap_or("Not started")
+    );
+    let _ = writeln!(bundle, "Driver: {}", snapshot.driver);
+
+    // The config holds no credentials, those live in their own store
+    let _ = writeln!(bundle, "\n## Config");
+    let _ = writeln!(
+        bundle,
+        "{}",
+        toml::to_string_pretty(config).unwrap_or_else(|error| error.to_string())
+    );
+
+    let _ = writeln!(bundle, "## Requests");
+    for request in &snapshot.requests {
+        let _ = writeln!(bundle, "{request}");
+    }
+
+    let _ = writeln!(bundle, "\n## Log");
+    for line in &snapshot.log {
+        let _ = writeln!(bundle, "{line}");
+    }
+
+    bundle
+}
+
+fn push<T>(buffer: &mut VecDeque<T>, item: T, capacity: usize) {
+    if buffer.len() == capacity {
+        buffer.pop_front();
+    }
+    buffer.push_back(item);
+}
+
+/// Tracing layer keeping the most recent log lines in memory, so they can be shown in the app
+pub struct Layer;
+
+impl<S> tracing_subscriber::Layer<S> for Layer
+where
+    S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+{
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        context: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let spans = context
+            .event_scope(event)
+            .map(|scope| {
+                scope
+                    .from_root()
+                    .map(|span| span.name())
+                    .collect::<Vec<_>>()
+                    .join(":")
+            })
+            .unwrap_or_default();
+
+        let mut visitor = MessageVisitor::default();
+        event.record(&mut visitor);
+
+        push(
+            &mut recorder().log,
+            LogLine {
+                time: Local::now(),
+                level: *event.metadata().level(),
+                spans,
+                message: visitor.0,
+            },
+            LOG_CAPACITY,
+        );
+    }
+}
+
+/// Formats an event as its message, followed by its other fields
+#[derive(Default)]
+struct MessageVisitor(String);
+
+impl tracing::field::Visit for MessageVisitor {
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        if field.name() == "message" {
+            self.0 = format!("{value:?}{}", self.0);
+        } else {
+            let _ = write!(self.0, " {}={value:?}", field.name());
+        }
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        if field.name() == "message" {
+            self.record_debug(field, &format_args!("{value}"));
+        } else {
+            let _ = write!(self.0, " {}={value}", field.name());
+        }
+    }
+}
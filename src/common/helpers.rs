@@ -1,13 +1,146 @@
-#[derive(Debug, Default, Clone)]
+/// Sign in details. Wiped from memory when dropped, and masked when formatted with `Debug`.
+#[derive(
+    Default, Clone, serde::Deserialize, serde::Serialize, zeroize::Zeroize, zeroize::ZeroizeOnDrop,
+)]
 pub struct Credentials {
     pub email: String,
-    pub password: String,
+    pub password: Secret,
+}
+
+impl std::fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Credentials")
+            .field("email", &mask_email(&self.email))
+            .field("password", &self.password)
+            .finish()
+    }
 }
 
+/// Keeps enough of an e-mail address to tell accounts apart in logs, e.g. "g***@example.com"
+fn mask_email(email: &str) -> String {
+    match email.split_once('@') {
+        Some((user, domain)) => {
+            let first = user.chars().next().map(String::from).unwrap_or_default();
+            format!("{first}***@{domain}")
+        }
+        None if email.is_empty() => String::new(),
+        None => "***".to_string(),
+    }
+}
+
+/// Text that must never show up in logs or error messages, like passwords. `Debug` prints a placeholder, and the
+/// text is wiped from memory when dropped.
 #[derive(
-    Clone, Copy, Debug, strum_macros::Display, strum_macros::EnumString, strum_macros::EnumIter,
+    Default,
+    Clone,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    zeroize::Zeroize,
+    zeroize::ZeroizeOnDrop,
 )]
-pub enum Mode {
-    Goodreads,
-    Steam,
+#[serde(transparent)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl From<String> for Secret {
+    fn from(secret: String) -> Self {
+        Self(secret)
+    }
+}
+
+impl std::fmt::Debug for Secret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\"********\"")
+    }
+}
+
+/// (De)serializes durations as a plain number of milliseconds, which is easier to edit by hand
+pub mod milliseconds {
+    use std::time::Duration;
+
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u64(duration.as_millis() as u64)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Duration, D::Error> {
+        Ok(Duration::from_millis(u64::deserialize(deserializer)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{backend, scene};
+
+    const PASSWORD: &str = "correct horse battery staple";
+
+    fn credentials() -> Credentials {
+        Credentials {
+            email: "godric@example.com".to_string(),
+            password: PASSWORD.to_string().into(),
+        }
+    }
+
+    fn assert_redacted(formatted: &[String]) {
+        for formatted in formatted {
+            assert!(
+                !formatted.contains(PASSWORD),
+                "Password leaked: {formatted}"
+            );
+            assert!(
+                !formatted.contains("godric@example.com"),
+                "E-mail leaked: {formatted}"
+            );
+        }
+    }
+
+    #[test]
+    fn credentials_are_redacted() {
+        let credentials = credentials();
+        assert_redacted(&[format!("{credentials:?}"), format!("{credentials:#?}")]);
+    }
+
+    #[test]
+    fn backend_inputs_are_redacted() {
+        let input = backend::Input::from(backend::welcome::Input::LoginAttempt {
+            credentials: credentials(),
+        });
+        assert_redacted(&[format!("{input:?}"), format!("{input:#?}")]);
+    }
+
+    #[test]
+    fn scene_messages_are_redacted() {
+        use scene::welcome::Message;
+
+        let messages = [
+            Message::PasswordInput(PASSWORD.to_string().into()),
+            Message::PassphraseInput(PASSWORD.to_string().into()),
+            Message::CredentialsUnlocked(Ok(credentials())),
+        ];
+        for message in messages {
+            // Unexpected messages end up in errors shown to the user
+            let error = backend::Error::InvalidState {
+                state: "Test".to_string(),
+                message: format!("{message:?}"),
+            };
+            assert_redacted(&[
+                format!("{message:?}"),
+                format!("{message:#?}"),
+                error.to_string(),
+            ]);
+        }
+    }
 }
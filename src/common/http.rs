@@ -0,0 +1,159 @@
+use std::{
+    sync::{Arc, LazyLock, RwLock},
+    time::Duration,
+};
+
+use tokio::{
+    sync::{Mutex, Semaphore},
+    time::Instant,
+};
+
+use crate::common::{diagnostics, helpers};
+
+static SCHEDULER: LazyLock<RwLock<Scheduler>> =
+    LazyLock::new(|| RwLock::new(Scheduler::new(SchedulerConfig::default())));
+
+/// The scheduler shared by everything sending requests to remote servers
+pub fn scheduler() -> Scheduler {
+    SCHEDULER
+        .read()
+        .expect("HTTP scheduler lock poisoned")
+        .clone()
+}
+
+/// Replaces the shared scheduler. Requests already waiting keep going through the old one.
+pub fn configure(config: SchedulerConfig) {
+    *SCHEDULER.write().expect("HTTP scheduler lock poisoned") = Scheduler::new(config);
+}
+
+#[derive(Clone, Debug, PartialEq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct SchedulerConfig {
+    pub max_concurrent_requests: usize,
+    pub requests_per_second: f64,
+    /// Upper bound of a random delay added between requests, so they don't arrive in a perfectly regular pattern
+    #[serde(rename = "jitter_ms", with = "helpers::milliseconds")]
+    pub jitter: Duration,
+    pub user_agent: String,
+}
+
+impl Default for SchedulerConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent_requests: 4,
+            requests_per_second: 10.0,
+            jitter: Duration::from_millis(30),
+            user_agent:
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64; rv:124.0) Gecko/20100101 Firefox/124.0"
+                    .to_string(),
+        }
+    }
+}
+
+/// Limits how many requests run at once and how quickly they are started, so we don't get rate-limited
+#[derive(Clone, Debug)]
+pub struct Scheduler {
+    client: reqwest::Client,
+    config: SchedulerConfig,
+    permits: Arc<Semaphore>,
+    next_start: Arc<Mutex<Instant>>,
+}
+
+impl Scheduler {
+    pub fn new(config: SchedulerConfig) -> Self {
+        let client = reqwest::Client::builder()
+            .user_agent(&config.user_agent)
+            .build()
+            .unwrap_or_default();
+
+        Self {
+            client,
+            permits: Arc::new(Semaphore::new(config.max_concurrent_requests.max(1))),
+            next_start: Arc::new(Mutex::new(Instant::now())),
+            config,
+        }
+    }
+
+    pub fn config(&self) -> &SchedulerConfig {
+        &self.config
+    }
+
+    /// Downloads the page at `url` as text
+    pub async fn get_text(&self, url: impl reqwest::IntoUrl) -> reqwest::Result<String> {
+        let url = url.into_url()?;
+        let _permit = self.turn().await;
+        self.get(url).await?.text().await
+    }
+
+    /// Downloads the resource at `url`, e.g. an image
+    pub async fn get_bytes(&self, url: impl reqwest::IntoUrl) -> reqwest::Result<Vec<u8>> {
+        let url = url.into_url()?;
+        let _permit = self.turn().await;
+        self.get(url)
+            .await?
+            .bytes()
+            .await
+            .map(|bytes| bytes.to_vec())
+    }
+
+    /// Loads the resource at `url` from `cache`, downloading it on a miss. The cache is only an optimization, so
+    /// failing to write to it isn't an error.
+    pub async fn get_cached(&self, url: &str, cache: &std::path::Path) -> reqwest::Result<Vec<u8>> {
+        // Image URLs tend to be unique per image, so their path makes for a usable file name
+        let file_name = url::Url::parse(url)
+            .map(|url| url.path().trim_matches('/').replace('/', "_"))
+            .unwrap_or_else(|_| url.replace(['/', ':', '?'], "_"));
+        let path = cache.join(file_name);
+
+        if let Ok(cached) = tokio::fs::read(&path).await {
+            return Ok(cached);
+        }
+
+        let downloaded = self.get_bytes(url).await?;
+        if tokio::fs::create_dir_all(cache).await.is_ok() {
+            let _ = tokio::fs::write(&path, &downloaded).await;
+        }
+
+        Ok(downloaded)
+    }
+
+    #[tracing::instrument(name = "http", skip(self), fields(%url))]
+    async fn get(&self, url: reqwest::Url) -> reqwest::Result<reqwest::Response> {
+        let start = std::time::Instant::now();
+        let response = self.client.get(url.clone()).send().await;
+        let elapsed = start.elapsed();
+        match &response {
+            Ok(response) => {
+                tracing::debug!(status = response.status().as_u16(), ?elapsed, "Response");
+                diagnostics::record_request(&url, Ok(response.status().as_u16()), elapsed);
+            }
+            Err(error) => {
+                tracing::warn!(?elapsed, "Request failed: {error}");
+                diagnostics::record_request(&url, Err(error.to_string()), elapsed);
+            }
+        }
+        response?.error_for_status()
+    }
+
+    /// Waits for a free slot and the next start time allowed by the rate limit. The slot is held until the
+    /// returned permit is dropped.
+    async fn turn(&self) -> tokio::sync::SemaphorePermit<'_> {
+        let permit = self
+            .permits
+            .acquire()
+            .await
+            .expect("HTTP scheduler semaphore closed");
+
+        let start = {
+            let mut next_start = self.next_start.lock().await;
+            let start = (*next_start).max(Instant::now());
+            let interval = Duration::from_secs_f64(1.0 / self.config.requests_per_second.max(0.01))
+                + self.config.jitter.mul_f64(rand::random::<f64>());
+            *next_start = start + interval;
+            start
+        };
+        tokio::time::sleep_until(start).await;
+
+        permit
+    }
+}
@@ -0,0 +1,127 @@
+use iced::keyboard::{Key, key::Named};
+
+/// Things that can be done from the keyboard while sorting
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum_macros::Display,
+    strum_macros::EnumIter,
+)]
+pub enum Action {
+    #[strum(to_string = "Pick left")]
+    PickLeft,
+    #[strum(to_string = "Pick right")]
+    PickRight,
+    Tie,
+    /// Compares another item first, coming back to the current one later
+    Skip,
+    Undo,
+    #[strum(to_string = "Expand description")]
+    ToggleBlurb,
+}
+
+/// Keys bound to each action. Keys are stored by name (see `key_name`), so bindings can be saved as text.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(from = "Vec<Binding>", into = "Vec<Binding>")]
+pub struct KeyBindings {
+    bindings: Vec<(Action, String)>,
+}
+
+/// A single binding as saved in the config
+#[derive(serde::Deserialize, serde::Serialize)]
+struct Binding {
+    action: Action,
+    key: String,
+}
+
+impl From<Vec<Binding>> for KeyBindings {
+    fn from(bindings: Vec<Binding>) -> Self {
+        Self {
+            bindings: bindings
+                .into_iter()
+                .map(|binding| (binding.action, binding.key))
+                .collect(),
+        }
+    }
+}
+
+impl From<KeyBindings> for Vec<Binding> {
+    fn from(bindings: KeyBindings) -> Self {
+        bindings
+            .bindings
+            .into_iter()
+            .map(|(action, key)| Binding { action, key })
+            .collect()
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            bindings: vec![
+                (Action::PickLeft, "ArrowLeft".to_string()),
+                (Action::PickRight, "ArrowRight".to_string()),
+                (Action::Tie, "ArrowDown".to_string()),
+                (Action::Skip, "ArrowUp".to_string()),
+                (Action::Skip, "s".to_string()),
+                (Action::Undo, "u".to_string()),
+                (Action::ToggleBlurb, "Space".to_string()),
+            ],
+        }
+    }
+}
+
+impl KeyBindings {
+    pub fn action(&self, key: &Key) -> Option<Action> {
+        let name = key_name(key)?;
+        self.bindings
+            .iter()
+            .find(|(_, bound)| *bound == name)
+            .map(|&(action, _)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> Vec<&str> {
+        self.bindings
+            .iter()
+            .filter(|(bound, _)| *bound == action)
+            .map(|(_, key)| key.as_str())
+            .collect()
+    }
+
+    /// Binds `key` to `action` instead of its previous keys. The key is taken away from any other action.
+    pub fn rebind(&mut self, action: Action, key: &Key) {
+        let Some(name) = key_name(key) else {
+            return;
+        };
+        self.bindings
+            .retain(|(bound, key)| *bound != action && *key != name);
+        self.bindings.push((action, name));
+    }
+}
+
+/// Name of a key, e.g. "ArrowLeft" or "s". Letters are case insensitive.
+pub fn key_name(key: &Key) -> Option<String> {
+    match key {
+        Key::Named(named) => Some(format!("{named:?}")),
+        Key::Character(character) => Some(character.to_lowercase()),
+        Key::Unidentified => None,
+    }
+}
+
+/// Digit of a key, for keys "0" to "9"
+pub fn digit(key: &Key) -> Option<u32> {
+    match key {
+        Key::Character(character) => character.parse().ok(),
+        _ => None,
+    }
+}
+
+/// Whether the key is the one used for cancelling, e.g. rebinding
+pub fn is_escape(key: &Key) -> bool {
+    *key == Key::Named(Named::Escape)
+}
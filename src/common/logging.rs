@@ -0,0 +1,83 @@
+use std::path::PathBuf;
+
+use color_eyre::eyre::Context;
+use tracing_appender::{non_blocking::WorkerGuard, rolling};
+use tracing_subscriber::{
+    EnvFilter, Layer, filter::Targets, layer::SubscriberExt, util::SubscriberInitExt,
+};
+
+/// Levels offered in the settings, from least to most verbose
+pub const LEVELS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// Number of rotated log files kept
+const KEPT_LOG_FILES: usize = 7;
+
+/// Where log files go, e.g. `~/.local/share/godric/logs` on Linux
+pub fn directory() -> PathBuf {
+    dirs::data_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("godric")
+        .join("logs")
+}
+
+/// Logs to stderr, to the diagnostics panel and to a file in `directory()`, starting a new file every day. For stderr and the file,
+/// `level` applies to Godric itself, while dependencies only log warnings. The `RUST_LOG` environment variable overrides both. The
+/// diagnostics panel gets every level and filters on its own.
+///
+/// Problems don't stop Godric from starting: an invalid `level` falls back to the default one, and without a log file only stderr
+/// and the diagnostics panel are logged to. Both are reported as warnings once logging runs.
+///
+/// Log lines are written in the background until the returned guard is dropped, so keep it around.
+pub fn init(level: &str) -> Option<WorkerGuard> {
+    let mut problems = Vec::new();
+
+    let filter = EnvFilter::try_from_default_env()
+        .or_else(|_| EnvFilter::try_new(format!("warn,godric={level}")))
+        .unwrap_or_else(|error| {
+            let default = super::config::Config::default().log_level;
+            problems.push(format!(
+                "Invalid log level \"{level}\", using \"{default}\" instead: {error}"
+            ));
+            EnvFilter::new(format!("warn,godric={default}"))
+        });
+
+    let file = rolling::Builder::new()
+        .rotation(rolling::Rotation::DAILY)
+        .filename_prefix("godric")
+        .filename_suffix("log")
+        .max_log_files(KEPT_LOG_FILES)
+        .build(directory())
+        .context("Failed to create log file")
+        .inspect_err(|error| problems.push(format!("Logging to stderr only. {error:#}")))
+        .ok();
+    let (file, guard) = file.map(tracing_appender::non_blocking).unzip();
+
+    let output = tracing_subscriber::fmt::layer()
+        .with_writer(std::io::stderr)
+        .and_then(file.map(|file| {
+            tracing_subscriber::fmt::layer()
+                .with_ansi(false)
+                .with_writer(file)
+        }))
+        .with_filter(filter);
+    // The diagnostics panel has a level picker of its own, so it gets all of Godric's events
+    let diagnostics = super::diagnostics::Layer.with_filter(
+        Targets::new()
+            .with_default(tracing::Level::WARN)
+            .with_target("godric", tracing::Level::TRACE),
+    );
+
+    let result = tracing_subscriber::registry()
+        .with(output)
+        .with(diagnostics)
+        .try_init();
+    if let Err(error) = result {
+        eprintln!("Failed to set up logging: {error}");
+    }
+
+    for problem in problems {
+        tracing::warn!("{problem}");
+    }
+
+    guard
+}
@@ -1,2 +1,11 @@
 pub mod browser;
+pub mod config;
+pub mod credential_store;
+pub mod diagnostics;
 pub mod helpers;
+pub mod http;
+pub mod keybindings;
+pub mod logging;
+pub mod selectors;
+pub mod sorting;
+pub mod webdriver;
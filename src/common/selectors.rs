@@ -0,0 +1,214 @@
+use std::{
+    path::PathBuf,
+    sync::{Arc, LazyLock, RwLock},
+    time::SystemTime,
+};
+
+use color_eyre::{
+    Result,
+    eyre::{Context, ContextCompat, eyre},
+};
+
+/// Selectors shipped with Godric, used unless overridden by `override_path()`
+pub const DEFAULT: &str = include_str!("selectors.toml");
+/// Files with a different version are ignored, as their entries may mean something else
+const VERSION: u32 = 1;
+
+static SELECTORS: LazyLock<RwLock<Loaded>> = LazyLock::new(|| {
+    RwLock::new(Loaded {
+        modified: None,
+        selectors: Arc::new(embedded()),
+    })
+});
+
+/// Selectors currently in use, along with the modification time of the override they were loaded from
+struct Loaded {
+    modified: Option<SystemTime>,
+    selectors: Arc<Selectors>,
+}
+
+/// The selectors to use right now. The override file is reloaded whenever it changed, so fixes apply without a
+/// restart. A broken override is reported in the log, and the previous selectors are kept.
+pub fn selectors() -> Arc<Selectors> {
+    let modified = override_path()
+        .and_then(|path| std::fs::metadata(path).ok())
+        .and_then(|metadata| metadata.modified().ok());
+
+    {
+        let loaded = SELECTORS.read().expect("Selector lock poisoned");
+        if loaded.modified == modified {
+            return loaded.selectors.clone();
+        }
+    }
+
+    let mut loaded = SELECTORS.write().expect("Selector lock poisoned");
+    if loaded.modified != modified {
+        loaded.modified = modified;
+        match modified {
+            // The override was removed
+            None => loaded.selectors = Arc::new(embedded()),
+            Some(_) => match load_override() {
+                Ok(selectors) => {
+                    tracing::info!("Loaded selector overrides");
+                    loaded.selectors = Arc::new(selectors);
+                }
+                Err(error) => tracing::error!("Ignoring selector overrides: {error:#}"),
+            },
+        }
+    }
+    loaded.selectors.clone()
+}
+
+/// Location of the override file, e.g. `~/.config/godric/selectors.toml` on Linux
+pub fn override_path() -> Option<PathBuf> {
+    Some(dirs::config_dir()?.join("godric").join("selectors.toml"))
+}
+
+/// Writes the default selectors to `override_path()` to be edited, unless there already is a file
+pub fn create_override() -> Result<PathBuf> {
+    let path = override_path().context("Unable to locate config directory")?;
+    if path.exists() {
+        return Ok(path);
+    }
+    if let Some(directory) = path.parent() {
+        std::fs::create_dir_all(directory)
+            .with_context(|| format!("Failed to create directory {}", directory.display()))?;
+    }
+    std::fs::write(&path, DEFAULT)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(path)
+}
+
+fn embedded() -> Selectors {
+    parse(DEFAULT).expect("Invalid built-in selectors")
+}
+
+fn load_override() -> Result<Selectors> {
+    let path = override_path().context("Unable to locate config directory")?;
+    let selectors = std::fs::read_to_string(&path)
+        .with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&selectors).with_context(|| format!("Invalid selectors in {}", path.display()))
+}
+
+fn parse(selectors: &str) -> Result<Selectors> {
+    let selectors: Selectors = toml::from_str(selectors)?;
+    if selectors.version != VERSION {
+        return Err(eyre!(
+            "Selectors are for version {}, but version {VERSION} is needed",
+            selectors.version
+        ));
+    }
+    Ok(selectors)
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Selectors {
+    pub version: u32,
+    pub shelf: Shelf,
+    pub book: Book,
+    pub sign_in: SignIn,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Shelf {
+    pub content: Css,
+    pub pagination: Css,
+    pub pagination_link: Css,
+    pub book_row: Css,
+    pub position: Css,
+    pub title: Css,
+    pub date_added: Css,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct Book {
+    pub title: Css,
+    pub author: Css,
+    pub blurb: Css,
+    pub cover: Css,
+    pub series: Css,
+    pub page_count: Css,
+    pub rating: Css,
+    pub publication_info: Css,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct SignIn {
+    pub email_sign_in_button: Vec<Locator>,
+    pub email_field: Vec<Locator>,
+    pub password_field: Vec<Locator>,
+    pub sign_in_button: Vec<Locator>,
+    pub profile_menu: Vec<Locator>,
+    pub otp_field: Vec<Locator>,
+    pub otp_submit_button: Vec<Locator>,
+    pub captcha_image: Vec<Locator>,
+    pub captcha_field: Vec<Locator>,
+    pub error_message: Vec<Locator>,
+}
+
+/// CSS selector for scraping HTML, checked when the selectors are loaded
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(try_from = "String")]
+pub struct Css(scraper::Selector);
+
+impl TryFrom<String> for Css {
+    type Error = String;
+
+    fn try_from(selector: String) -> Result<Self, Self::Error> {
+        scraper::Selector::parse(&selector)
+            .map(Self)
+            .map_err(|error| format!("Invalid CSS selector {selector:?}: {error}"))
+    }
+}
+
+impl std::ops::Deref for Css {
+    type Target = scraper::Selector;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Way of finding an element through WebDriver
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Locator {
+    Id(String),
+    Name(String),
+    Css(String),
+    #[serde(rename = "xpath")]
+    XPath(String),
+}
+
+impl From<&Locator> for thirtyfour::By {
+    fn from(locator: &Locator) -> Self {
+        match locator {
+            Locator::Id(id) => Self::Id(id.clone()),
+            Locator::Name(name) => Self::Name(name.clone()),
+            Locator::Css(css) => Self::Css(css.clone()),
+            Locator::XPath(xpath) => Self::XPath(xpath.clone()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_selectors_parse() {
+        let selectors = parse(DEFAULT).expect("Invalid built-in selectors");
+        assert_eq!(selectors.version, VERSION);
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let outdated = DEFAULT.replacen(
+            &format!("version = {VERSION}"),
+            &format!("version = {}", VERSION + 1),
+            1,
+        );
+        assert_ne!(outdated, DEFAULT);
+        assert!(parse(&outdated).is_err());
+    }
+}
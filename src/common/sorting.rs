@@ -0,0 +1,1070 @@
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, VecDeque},
+};
+
+/// Number of answers and moves that can be undone
+const HISTORY_LIMIT: usize = 100;
+
+/// Metadata that a list can be sorted by automatically
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
+)]
+pub enum SortKey {
+    #[strum(to_string = "Series number")]
+    SeriesNumber,
+    #[strum(to_string = "Date added")]
+    DateAdded,
+    #[strum(to_string = "Page count")]
+    PageCount,
+    Rating,
+    #[strum(to_string = "Publication year")]
+    PublicationYear,
+    Title,
+    Author,
+    Price,
+    #[strum(to_string = "Release date")]
+    ReleaseDate,
+}
+
+impl SortKey {
+    /// Keys that books on a Goodreads shelf have values for
+    pub const BOOKS: [SortKey; 7] = [
+        SortKey::SeriesNumber,
+        SortKey::DateAdded,
+        SortKey::PageCount,
+        SortKey::Rating,
+        SortKey::PublicationYear,
+        SortKey::Title,
+        SortKey::Author,
+    ];
+    /// Keys that games on a Steam wishlist have values for
+    pub const GAMES: [SortKey; 4] = [
+        SortKey::DateAdded,
+        SortKey::Price,
+        SortKey::ReleaseDate,
+        SortKey::Title,
+    ];
+}
+
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    serde::Deserialize,
+    serde::Serialize,
+    strum_macros::Display,
+    strum_macros::EnumString,
+    strum_macros::EnumIter,
+)]
+pub enum Direction {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+pub struct Rule {
+    pub key: SortKey,
+    pub direction: Direction,
+}
+
+impl Rule {
+    pub fn new(key: SortKey, direction: Direction) -> Self {
+        Self { key, direction }
+    }
+}
+
+/// Value of a `SortKey` for a single item
+#[derive(Clone, Debug, PartialEq, PartialOrd)]
+pub enum Value {
+    Number(f64),
+    Text(String),
+}
+
+/// Sorts `order` (indices into some list of items) by the given rules. Earlier rules take precedence,
+/// later rules only break ties. Items without a value for a key are placed last, regardless of direction.
+pub fn auto_sort<F>(order: &mut [usize], rules: &[Rule], value: F)
+where
+    F: Fn(usize, SortKey) -> Option<Value>,
+{
+    // Sorting is stable, so items that compare equal under all rules keep their current relative order
+    order.sort_by(|&a, &b| {
+        rules
+            .iter()
+            .map(|rule| compare(value(a, rule.key), value(b, rule.key), rule.direction))
+            .find(|ordering| ordering.is_ne())
+            .unwrap_or(Ordering::Equal)
+    });
+}
+
+fn compare(a: Option<Value>, b: Option<Value>, direction: Direction) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ordering = a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+            match direction {
+                Direction::Ascending => ordering,
+                Direction::Descending => ordering.reverse(),
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+/// Hard requirements on the order of items, such as earlier volumes of a series coming before later ones,
+/// or items pinned to a fixed position
+#[derive(Clone, Debug, Default)]
+pub struct Constraints {
+    /// Pairs of (earlier, later) items
+    precedences: Vec<(usize, usize)>,
+    /// Pairs of (item, position), with positions starting at 0
+    pins: Vec<(usize, usize)>,
+}
+
+/// A reason why a set of constraints can't be satisfied
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Conflict {
+    /// Items that would all have to come before themselves
+    Cycle(Vec<usize>),
+    /// Several items pinned to the same position
+    SharedPosition { position: usize, items: Vec<usize> },
+    /// An item pinned to a position beyond the end of the list
+    OutOfRange { item: usize, position: usize },
+    /// A precedence that can't hold, given where items are pinned
+    Unsatisfiable { earlier: usize, later: usize },
+}
+
+impl Constraints {
+    pub fn add(&mut self, earlier: usize, later: usize) {
+        if !self.precedences.contains(&(earlier, later)) {
+            self.precedences.push((earlier, later));
+        }
+    }
+
+    pub fn remove(&mut self, earlier: usize, later: usize) {
+        self.precedences
+            .retain(|&precedence| precedence != (earlier, later));
+    }
+
+    /// Pins `item` to `position`, replacing any previous pin of the item
+    pub fn pin(&mut self, item: usize, position: usize) {
+        self.unpin(item);
+        self.pins.push((item, position));
+    }
+
+    pub fn unpin(&mut self, item: usize) {
+        self.pins.retain(|&(pinned, _)| pinned != item);
+    }
+
+    pub fn precedences(&self) -> &[(usize, usize)] {
+        &self.precedences
+    }
+
+    pub fn pins(&self) -> &[(usize, usize)] {
+        &self.pins
+    }
+
+    pub fn pinned_position(&self, item: usize) -> Option<usize> {
+        self.pins
+            .iter()
+            .find(|(pinned, _)| *pinned == item)
+            .map(|&(_, position)| position)
+    }
+
+    /// Combination of both sets of constraints
+    pub fn union(&self, other: &Constraints) -> Constraints {
+        let mut union = self.clone();
+        for &(earlier, later) in &other.precedences {
+            union.add(earlier, later);
+        }
+        for &(item, position) in &other.pins {
+            union.pin(item, position);
+        }
+        union
+    }
+
+    /// Whether `earlier` has to come before `later`, either directly or through a chain of constraints
+    pub fn requires(&self, earlier: usize, later: usize) -> bool {
+        let mut visited = vec![earlier];
+        let mut stack = vec![earlier];
+        while let Some(item) = stack.pop() {
+            for &(_, next) in self.precedences.iter().filter(|(from, _)| *from == item) {
+                if next == later {
+                    return true;
+                }
+                if !visited.contains(&next) {
+                    visited.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+        false
+    }
+
+    /// Reorders `order` to satisfy all constraints, moving items as little as possible otherwise.
+    /// Contradicting constraints are broken at the first item they block, in the given order.
+    pub fn enforce(&self, order: &[usize]) -> Vec<usize> {
+        let mut remaining = order.to_vec();
+        let mut result = Vec::with_capacity(order.len());
+
+        // Items that have to come before a pinned item are due by its position
+        let deadline = |item: usize| {
+            self.pins
+                .iter()
+                .filter(|&&(pinned, _)| order.contains(&pinned) && self.requires(item, pinned))
+                .map(|&(_, position)| position)
+                .min()
+        };
+        let deadlines: HashMap<usize, usize> = order
+            .iter()
+            .filter_map(|&item| Some((item, deadline(item)?)))
+            .collect();
+
+        // Fill the positions in order. A pinned item takes its position when it comes up, otherwise it goes to
+        // the first free item that doesn't have to wait for any of the remaining ones, preferring those due
+        // soonest.
+        while !remaining.is_empty() {
+            let slot = result.len();
+            let waits = |item: usize, remaining: &[usize]| {
+                self.precedences
+                    .iter()
+                    .any(|&(earlier, later)| later == item && remaining.contains(&earlier))
+            };
+            let free = |item: usize| {
+                self.pinned_position(item)
+                    .is_none_or(|position| position < slot)
+            };
+
+            let next = remaining
+                .iter()
+                .position(|&item| self.pinned_position(item) == Some(slot))
+                .or_else(|| {
+                    remaining
+                        .iter()
+                        .enumerate()
+                        .filter(|&(_, &item)| free(item) && !waits(item, &remaining))
+                        .min_by_key(|&(index, item)| {
+                            (deadlines.get(item).copied().unwrap_or(usize::MAX), index)
+                        })
+                        .map(|(index, _)| index)
+                })
+                // Everything left is blocked by contradicting constraints or pinned beyond the end of the list
+                .or_else(|| remaining.iter().position(|&item| free(item)))
+                .or_else(|| {
+                    (0..remaining.len()).min_by_key(|&index| self.pinned_position(remaining[index]))
+                })
+                .expect("No item left to place");
+            result.push(remaining.remove(next));
+        }
+
+        result
+    }
+
+    /// Everything preventing the constraints from being satisfied for a list of `len` items
+    pub fn conflicts(&self, len: usize) -> Vec<Conflict> {
+        let mut conflicts = vec![];
+
+        let mut cyclic: Vec<usize> = vec![];
+        for &(earlier, later) in &self.precedences {
+            if self.requires(later, earlier) {
+                for item in [earlier, later] {
+                    if !cyclic.contains(&item) {
+                        cyclic.push(item);
+                    }
+                }
+            }
+        }
+        if !cyclic.is_empty() {
+            conflicts.push(Conflict::Cycle(cyclic));
+        }
+
+        let mut positions: Vec<_> = self.pins.iter().map(|&(_, position)| position).collect();
+        positions.sort();
+        positions.dedup();
+        for position in positions {
+            let items: Vec<_> = self
+                .pins
+                .iter()
+                .filter(|&&(_, pinned)| pinned == position)
+                .map(|&(item, _)| item)
+                .collect();
+            if items.len() > 1 {
+                conflicts.push(Conflict::SharedPosition { position, items });
+            }
+        }
+
+        for &(item, position) in &self.pins {
+            if position >= len {
+                conflicts.push(Conflict::OutOfRange { item, position });
+            }
+        }
+
+        // Whatever is left shows up as a violated precedence in the best possible order
+        if conflicts.is_empty() {
+            let order = self.enforce(&(0..len).collect::<Vec<_>>());
+            let position = |item| order.iter().position(|&other| other == item);
+            for &(earlier, later) in &self.precedences {
+                if position(earlier) > position(later) {
+                    conflicts.push(Conflict::Unsatisfiable { earlier, later });
+                }
+            }
+        }
+
+        conflicts
+    }
+
+    /// Maps every constrained item to a group, such that items linked by constraints share a group
+    pub fn groups(&self) -> HashMap<usize, usize> {
+        let mut groups: HashMap<usize, usize> = HashMap::new();
+        for &(earlier, later) in &self.precedences {
+            let group = match (groups.get(&earlier), groups.get(&later)) {
+                (Some(&a), Some(&b)) => {
+                    // Merge the two groups
+                    groups
+                        .values_mut()
+                        .filter(|group| **group == b)
+                        .for_each(|group| *group = a);
+                    a
+                }
+                (Some(&group), None) | (None, Some(&group)) => group,
+                (None, None) => earlier,
+            };
+            groups.insert(earlier, group);
+            groups.insert(later, group);
+        }
+        groups
+    }
+}
+
+/// Ordering of one list, combining automatic sorting, comparisons and constraints
+#[derive(Clone, Debug, Default)]
+pub struct Session {
+    order: Vec<usize>,
+    ranking: Option<Ranking>,
+    /// Constraints following from the items themselves, e.g. their series
+    derived: Constraints,
+    /// Constraints added by the user
+    manual: Constraints,
+    /// Earlier states, for undoing answers and moves, the latest last
+    history: VecDeque<Snapshot>,
+}
+
+#[derive(Clone, Debug)]
+struct Snapshot {
+    order: Vec<usize>,
+    ranking: Option<Ranking>,
+    /// Manual constraints, for the few actions that change them, like moving a pinned item
+    manual: Option<Constraints>,
+}
+
+impl Session {
+    pub fn new(len: usize) -> Self {
+        Self {
+            order: (0..len).collect(),
+            ..Default::default()
+        }
+    }
+
+    /// Current order, as indices into the list
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn ranking(&self) -> Option<&Ranking> {
+        self.ranking.as_ref()
+    }
+
+    /// All constraints in effect
+    pub fn constraints(&self) -> Constraints {
+        self.derived.union(&self.manual)
+    }
+
+    pub fn manual_constraints(&self) -> &Constraints {
+        &self.manual
+    }
+
+    pub fn conflicts(&self) -> Vec<Conflict> {
+        self.constraints().conflicts(self.order.len())
+    }
+
+    pub fn set_derived_constraints(&mut self, constraints: Constraints) {
+        self.derived = constraints;
+        self.refresh();
+    }
+
+    pub fn add_precedence(&mut self, earlier: usize, later: usize) {
+        self.manual.add(earlier, later);
+        self.refresh();
+    }
+
+    pub fn remove_precedence(&mut self, earlier: usize, later: usize) {
+        self.manual.remove(earlier, later);
+        self.refresh();
+    }
+
+    pub fn pin(&mut self, item: usize, position: usize) {
+        self.manual.pin(item, position);
+        self.refresh();
+    }
+
+    pub fn unpin(&mut self, item: usize) {
+        self.manual.unpin(item);
+        self.refresh();
+    }
+
+    /// Sorts by the given rules. This replaces any comparisons in progress, and serves as the starting point
+    /// for the next ones.
+    pub fn auto_sort<F>(&mut self, rules: &[Rule], value: F)
+    where
+        F: Fn(usize, SortKey) -> Option<Value>,
+    {
+        self.save();
+        self.ranking = None;
+        auto_sort(&mut self.order, rules, value);
+        self.refresh();
+    }
+
+    /// Starts ranking the current order through comparisons. Pinned items already have their place, so
+    /// they are left out.
+    pub fn start_comparisons(&mut self) {
+        let constraints = self.constraints();
+        let order = self
+            .order
+            .iter()
+            .copied()
+            .filter(|&item| constraints.pinned_position(item).is_none())
+            .collect();
+        self.ranking = Some(Ranking::new(order, constraints));
+        self.refresh();
+    }
+
+    pub fn answer(&mut self, preference: Preference) {
+        if !self.is_comparing() {
+            return;
+        }
+        self.save();
+        if let Some(ranking) = &mut self.ranking {
+            ranking.answer(preference);
+        }
+        self.refresh();
+    }
+
+    /// Postpones the current comparison
+    pub fn skip(&mut self) {
+        if !self.can_skip() {
+            return;
+        }
+        self.save();
+        if let Some(ranking) = &mut self.ranking {
+            ranking.skip();
+        }
+        self.refresh();
+    }
+
+    /// Whether there is a comparison that can be postponed in favour of another one
+    pub fn can_skip(&self) -> bool {
+        self.ranking.as_ref().is_some_and(Ranking::can_skip)
+    }
+
+    /// Whether there is a comparison waiting for an answer
+    pub fn is_comparing(&self) -> bool {
+        self.ranking
+            .as_ref()
+            .is_some_and(|ranking| !ranking.is_finished())
+    }
+
+    /// Starts another round of comparisons for items that were tied. Returns whether there were any.
+    pub fn refine_ties(&mut self) -> bool {
+        if self.is_comparing() {
+            return false;
+        }
+        self.save();
+        let refining = self.ranking.as_mut().is_some_and(Ranking::refine);
+        if !refining {
+            self.history.pop_back();
+        }
+        self.refresh();
+        refining
+    }
+
+    /// Reverts the last answer, skip, move or automatic sort
+    pub fn undo(&mut self) {
+        if let Some(snapshot) = self.history.pop_back() {
+            self.order = snapshot.order;
+            self.ranking = snapshot.ranking;
+            if let Some(manual) = snapshot.manual {
+                self.manual = manual;
+            }
+            self.refresh();
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.history.is_empty()
+    }
+
+    fn save(&mut self) {
+        if self.history.len() == HISTORY_LIMIT {
+            self.history.pop_front();
+        }
+        self.history.push_back(Snapshot {
+            order: self.order.clone(),
+            ranking: self.ranking.clone(),
+            manual: None,
+        });
+    }
+
+    /// Moves `item` to `position` by hand. During comparisons, the item counts as ranked at its new place.
+    /// Pinned items are pinned to their new position instead.
+    pub fn move_item(&mut self, item: usize, position: usize) {
+        self.save();
+        if self.manual.pinned_position(item).is_some() {
+            if let Some(snapshot) = self.history.back_mut() {
+                snapshot.manual = Some(self.manual.clone());
+            }
+            self.pin(item, position);
+            return;
+        }
+
+        self.order.retain(|&other| other != item);
+        self.order.insert(position.min(self.order.len()), item);
+        if let Some(ranking) = &mut self.ranking {
+            ranking.place(item, &self.order);
+        }
+        self.refresh();
+    }
+
+    fn refresh(&mut self) {
+        let constraints = self.constraints();
+        if let Some(ranking) = &mut self.ranking {
+            ranking.set_constraints(constraints.clone());
+            // Items pinned at the start are not part of the ranking, so add them back in
+            let mut order = ranking.order();
+            let missing: Vec<_> = self
+                .order
+                .iter()
+                .copied()
+                .filter(|item| !order.contains(item))
+                .collect();
+            order.extend(missing);
+            self.order = order;
+        }
+        self.order = constraints.enforce(&self.order);
+    }
+}
+
+/// Answer to a comparison between two items
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Preference {
+    First,
+    Second,
+    /// Both items are equally good, or the user can't decide
+    Tie,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Insertion {
+    item: usize,
+    low: usize,
+    high: usize,
+    probe: usize,
+}
+
+/// Manual ordering through pairwise comparisons, using binary insertion.
+///
+/// Items are inserted in the order they were given, so a good starting order (e.g. from `auto_sort`) pays off:
+/// every insertion first probes the end of the already sorted items, which confirms a correctly placed item
+/// with a single comparison. Comparisons already decided by constraints are answered automatically.
+///
+/// Tied items end up in a shared bucket, where they keep the order they were given in. The buckets can be
+/// refined later on, through another ranking of just their items.
+#[derive(Clone, Debug)]
+pub struct Ranking {
+    /// Ranked items, as buckets of items considered equal
+    sorted: Vec<Vec<usize>>,
+    pending: VecDeque<usize>,
+    insertion: Option<Insertion>,
+    /// Bucket being refined, along with the ranking of its items
+    refinement: Option<(usize, Box<Ranking>)>,
+    /// Bucket to look for tied items from when refining, so that every bucket gets its turn before one that
+    /// was tied again
+    next_refinement: usize,
+    constraints: Constraints,
+    comparisons: usize,
+}
+
+impl Ranking {
+    pub fn new(order: Vec<usize>, constraints: Constraints) -> Self {
+        let mut pending: VecDeque<_> = order.into();
+        let sorted = pending
+            .pop_front()
+            .map(|item| vec![item])
+            .into_iter()
+            .collect();
+        let mut ranking = Self {
+            sorted,
+            pending,
+            insertion: None,
+            refinement: None,
+            next_refinement: 0,
+            constraints,
+            comparisons: 0,
+        };
+        ranking.next_insertion();
+        ranking.resolve();
+        ranking
+    }
+
+    /// Replaces the constraints, e.g. when more is learned about the items while ranking them
+    pub fn set_constraints(&mut self, constraints: Constraints) {
+        if let Some((_, refinement)) = &mut self.refinement {
+            refinement.set_constraints(constraints.clone());
+        }
+        self.constraints = constraints;
+        self.resolve();
+        self.finish_refinement();
+    }
+
+    /// The pair of items the user should compare next, or `None` once the ranking is complete
+    pub fn comparison(&self) -> Option<(usize, usize)> {
+        if let Some((_, refinement)) = &self.refinement {
+            return refinement.comparison();
+        }
+
+        self.insertion
+            .map(|insertion| (insertion.item, self.sorted[insertion.probe][0]))
+    }
+
+    /// Records the answer to the current comparison. `Preference::First` places the first item of the
+    /// comparison ahead of the second one.
+    pub fn answer(&mut self, preference: Preference) {
+        if let Some((_, refinement)) = &mut self.refinement {
+            refinement.answer(preference);
+            self.comparisons += 1;
+            self.finish_refinement();
+            return;
+        }
+
+        if self.insertion.is_none() {
+            return;
+        }
+        self.comparisons += 1;
+        self.step(preference);
+        self.resolve();
+    }
+
+    fn step(&mut self, preference: Preference) {
+        let Some(mut insertion) = self.insertion else {
+            return;
+        };
+
+        match preference {
+            Preference::First => insertion.high = insertion.probe,
+            Preference::Second => insertion.low = insertion.probe + 1,
+            Preference::Tie => {
+                self.sorted[insertion.probe].push(insertion.item);
+                self.next_insertion();
+                return;
+            }
+        }
+
+        if insertion.low == insertion.high {
+            self.sorted.insert(insertion.low, vec![insertion.item]);
+            self.next_insertion();
+        } else {
+            insertion.probe = (insertion.low + insertion.high) / 2;
+            self.insertion = Some(insertion);
+        }
+    }
+
+    /// Whether another item is waiting, which could be inserted instead of the current one
+    pub fn can_skip(&self) -> bool {
+        match &self.refinement {
+            Some((_, refinement)) => refinement.can_skip(),
+            None => self.insertion.is_some() && !self.pending.is_empty(),
+        }
+    }
+
+    /// Moves the item currently being inserted to the back of the queue, to be compared again later
+    pub fn skip(&mut self) {
+        if let Some((_, refinement)) = &mut self.refinement {
+            refinement.skip();
+            return;
+        }
+
+        if let Some(insertion) = self.insertion
+            && !self.pending.is_empty()
+        {
+            self.pending.push_back(insertion.item);
+            self.next_insertion();
+            self.resolve();
+        }
+    }
+
+    /// Starts ranking the items of the next bucket of tied items. Returns whether there was one.
+    pub fn refine(&mut self) -> bool {
+        if !self.is_finished() {
+            return false;
+        }
+
+        let tied = |start: usize| {
+            self.sorted
+                .iter()
+                .skip(start)
+                .position(|bucket| bucket.len() > 1)
+                .map(|index| start + index)
+        };
+        // Once every bucket had its turn, start over with those that were tied again
+        match tied(self.next_refinement).or_else(|| tied(0)) {
+            Some(index) => {
+                let ranking = Ranking::new(self.sorted[index].clone(), self.constraints.clone());
+                self.refinement = Some((index, Box::new(ranking)));
+                self.finish_refinement();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Replaces the refined bucket with the buckets of its ranking, once that is complete
+    fn finish_refinement(&mut self) {
+        let Some((index, refinement)) = self
+            .refinement
+            .take_if(|(_, refinement)| refinement.is_finished())
+        else {
+            return;
+        };
+        self.next_refinement = index + refinement.sorted.len();
+        self.sorted.splice(index..=index, refinement.sorted);
+    }
+
+    /// Number of buckets with tied items
+    pub fn ties(&self) -> usize {
+        self.sorted.iter().filter(|bucket| bucket.len() > 1).count()
+    }
+
+    /// Ranks `item` where it appears in `order` relative to the other ranked items, as if the user had
+    /// compared it to all of them
+    pub fn place(&mut self, item: usize, order: &[usize]) {
+        let Some(position) = order.iter().position(|&other| other == item) else {
+            return;
+        };
+
+        // Manual moves and refinement don't mix well, so a refinement in progress is abandoned
+        self.refinement = None;
+        for bucket in &mut self.sorted {
+            bucket.retain(|&other| other != item);
+        }
+        self.sorted.retain(|bucket| !bucket.is_empty());
+        self.pending.retain(|&other| other != item);
+
+        let index = self
+            .sorted
+            .iter()
+            .filter(|bucket| order[..position].contains(&bucket[0]))
+            .count();
+        self.sorted.insert(index, vec![item]);
+
+        // The ranked items changed under the current insertion, so it has to start over
+        match self.insertion {
+            Some(insertion) if insertion.item == item => self.next_insertion(),
+            Some(insertion) => {
+                self.pending.push_front(insertion.item);
+                self.next_insertion();
+            }
+            None => {}
+        }
+        self.resolve();
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.insertion.is_none() && self.refinement.is_none()
+    }
+
+    pub fn comparisons(&self) -> usize {
+        self.comparisons
+    }
+
+    /// Estimate of the comparisons left, from the information-theoretic bound: Inserting an item into `n`
+    /// ranked buckets has `n + 1` possible outcomes, so it takes about `log2(n + 1)` answers
+    pub fn remaining_comparisons(&self) -> usize {
+        if let Some((_, refinement)) = &self.refinement {
+            return refinement.remaining_comparisons();
+        }
+
+        let Some(insertion) = self.insertion else {
+            return 0;
+        };
+
+        let current = ((insertion.high - insertion.low + 1) as f64).log2();
+        let buckets = self.sorted.len() + 1;
+        let pending: f64 = (0..self.pending.len())
+            .map(|i| ((buckets + i + 1) as f64).log2())
+            .sum();
+        (current + pending).ceil() as usize
+    }
+
+    /// Best known order: The ranked items, followed by those that haven't been ranked yet
+    pub fn order(&self) -> Vec<usize> {
+        let order: Vec<_> = self
+            .sorted
+            .iter()
+            .enumerate()
+            .flat_map(|(index, bucket)| match &self.refinement {
+                Some((refined, refinement)) if *refined == index => refinement.order(),
+                _ => bucket.clone(),
+            })
+            .chain(self.insertion.map(|insertion| insertion.item))
+            .chain(self.pending.iter().copied())
+            .collect();
+        self.constraints.enforce(&order)
+    }
+
+    /// Answers all comparisons that the constraints already decide
+    fn resolve(&mut self) {
+        while let Some(insertion) = self.insertion {
+            let item = insertion.item;
+            let bucket = &self.sorted[insertion.probe];
+            let preference = if bucket
+                .iter()
+                .any(|&other| self.constraints.requires(item, other))
+            {
+                Preference::First
+            } else if bucket
+                .iter()
+                .any(|&other| self.constraints.requires(other, item))
+            {
+                Preference::Second
+            } else {
+                break;
+            };
+            self.step(preference);
+        }
+    }
+
+    fn next_insertion(&mut self) {
+        self.insertion = self.pending.pop_front().map(|item| Insertion {
+            item,
+            low: 0,
+            high: self.sorted.len(),
+            probe: self.sorted.len() - 1,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Answers every comparison with `answer`, given the two items compared
+    fn rank(ranking: &mut Ranking, answer: impl Fn(usize, usize) -> Preference) {
+        while let Some((first, second)) = ranking.comparison() {
+            ranking.answer(answer(first, second));
+        }
+    }
+
+    /// Prefers lower items, as if the items were already numbered by preference
+    fn by_number(first: usize, second: usize) -> Preference {
+        match first.cmp(&second) {
+            Ordering::Less => Preference::First,
+            Ordering::Greater => Preference::Second,
+            Ordering::Equal => Preference::Tie,
+        }
+    }
+
+    #[test]
+    fn later_rules_break_ties_and_missing_values_go_last() {
+        let values = [
+            (Some(2.0), Some("b")),
+            (None, Some("a")),
+            (Some(1.0), Some("c")),
+            (Some(2.0), Some("a")),
+            (None, None),
+        ];
+        let value = |i: usize, key| {
+            let (number, text) = values[i];
+            match key {
+                SortKey::PageCount => number.map(Value::Number),
+                SortKey::Title => text.map(|text| Value::Text(text.to_string())),
+                _ => None,
+            }
+        };
+
+        let mut order: Vec<_> = (0..values.len()).collect();
+        auto_sort(
+            &mut order,
+            &[
+                Rule::new(SortKey::PageCount, Direction::Descending),
+                Rule::new(SortKey::Title, Direction::Ascending),
+            ],
+            value,
+        );
+        assert_eq!(order, [3, 0, 2, 1, 4]);
+    }
+
+    #[test]
+    fn ranking_sorts_by_answers() {
+        let mut ranking = Ranking::new(vec![3, 0, 4, 1, 2], Constraints::default());
+        rank(&mut ranking, by_number);
+        assert!(ranking.is_finished());
+        assert_eq!(ranking.order(), [0, 1, 2, 3, 4]);
+        assert_eq!(ranking.ties(), 0);
+    }
+
+    #[test]
+    fn ranking_keeps_tied_items_in_given_order() {
+        // 2 and 0 are equally good, and so are 3 and 1
+        let group = |item: usize| item % 2;
+        let mut ranking = Ranking::new(vec![2, 3, 0, 1], Constraints::default());
+        rank(&mut ranking, |first, second| {
+            by_number(group(first), group(second))
+        });
+        assert_eq!(ranking.order(), [2, 0, 3, 1]);
+        assert_eq!(ranking.ties(), 2);
+    }
+
+    #[test]
+    fn refining_reaches_every_tied_bucket() {
+        let mut ranking = Ranking::new(vec![0, 1, 2, 3], Constraints::default());
+        // Two buckets, {0, 1} and {2, 3}
+        rank(&mut ranking, |first, second| {
+            by_number(first / 2, second / 2)
+        });
+        assert_eq!(ranking.ties(), 2);
+
+        // Tying the first bucket again moves on to the second one
+        assert!(ranking.refine());
+        assert_eq!(ranking.comparison(), Some((1, 0)));
+        ranking.answer(Preference::Tie);
+        assert!(ranking.refine());
+        assert_eq!(ranking.comparison(), Some((3, 2)));
+        ranking.answer(Preference::First);
+        assert_eq!(ranking.order(), [0, 1, 3, 2]);
+
+        // Then back to the first one
+        assert!(ranking.refine());
+        assert_eq!(ranking.comparison(), Some((1, 0)));
+    }
+
+    #[test]
+    fn ranking_answers_comparisons_decided_by_constraints() {
+        let mut constraints = Constraints::default();
+        constraints.add(0, 1);
+        let mut ranking = Ranking::new(vec![1, 0], constraints);
+        assert_eq!(ranking.comparison(), None);
+        assert_eq!(ranking.order(), [0, 1]);
+    }
+
+    #[test]
+    fn skip_postpones_the_current_item() {
+        let mut ranking = Ranking::new(vec![0, 1, 2], Constraints::default());
+        assert_eq!(ranking.comparison(), Some((1, 0)));
+        ranking.skip();
+        assert_eq!(ranking.comparison(), Some((2, 0)));
+        rank(&mut ranking, by_number);
+        assert_eq!(ranking.order(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn skip_needs_another_item_to_compare() {
+        let mut ranking = Ranking::new(vec![0, 1], Constraints::default());
+        assert!(!ranking.can_skip());
+        ranking.skip();
+        assert_eq!(ranking.comparison(), Some((1, 0)));
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut session = Session::new(2);
+        for _ in 0..HISTORY_LIMIT + 10 {
+            session.move_item(0, 1);
+        }
+        for _ in 0..HISTORY_LIMIT {
+            assert!(session.can_undo());
+            session.undo();
+        }
+        assert!(!session.can_undo());
+    }
+
+    #[test]
+    fn pins_and_precedences_are_solved_together() {
+        // A (0) pinned to #2 and before B (1)
+        let mut constraints = Constraints::default();
+        constraints.pin(0, 1);
+        constraints.add(0, 1);
+        assert_eq!(constraints.enforce(&[0, 1, 2, 3]), [2, 0, 1, 3]);
+        assert!(constraints.conflicts(4).is_empty());
+    }
+
+    #[test]
+    fn items_due_before_a_pinned_item_come_first() {
+        // 1 has to come before 2, which is pinned to #2
+        let mut constraints = Constraints::default();
+        constraints.pin(2, 1);
+        constraints.add(1, 2);
+        assert_eq!(constraints.enforce(&[0, 1, 2]), [1, 2, 0]);
+        assert!(constraints.conflicts(3).is_empty());
+    }
+
+    #[test]
+    fn pins_that_contradict_precedences_are_unsatisfiable() {
+        let mut constraints = Constraints::default();
+        constraints.pin(1, 0);
+        constraints.add(0, 1);
+        assert_eq!(
+            constraints.conflicts(3),
+            [Conflict::Unsatisfiable {
+                earlier: 0,
+                later: 1
+            }]
+        );
+    }
+
+    #[test]
+    fn cycles_are_reported_and_broken_where_they_block() {
+        let mut constraints = Constraints::default();
+        constraints.add(0, 1);
+        constraints.add(1, 2);
+        constraints.add(2, 0);
+        assert_eq!(constraints.enforce(&[3, 1, 0, 2]), [3, 1, 2, 0]);
+        assert_eq!(constraints.conflicts(4), [Conflict::Cycle(vec![0, 1, 2])]);
+    }
+
+    #[test]
+    fn undo_reverts_answers_and_moves() {
+        let mut session = Session::new(3);
+        session.start_comparisons();
+        let before = session.ranking().and_then(Ranking::comparison);
+        session.answer(Preference::Second);
+        assert_ne!(session.ranking().and_then(Ranking::comparison), before);
+        session.undo();
+        assert_eq!(session.ranking().and_then(Ranking::comparison), before);
+
+        session.move_item(2, 0);
+        assert_eq!(session.order()[0], 2);
+        session.undo();
+        assert_eq!(session.order(), [0, 1, 2]);
+        assert!(!session.can_undo());
+
+        session.pin(1, 1);
+        session.move_item(1, 2);
+        assert_eq!(session.order(), [0, 2, 1]);
+        session.undo();
+        assert_eq!(session.order(), [0, 1, 2]);
+        assert_eq!(session.manual_constraints().pinned_position(1), Some(1));
+    }
+}
@@ -0,0 +1,236 @@
+use std::time::Duration;
+
+use thirtyfour as tf;
+use tracing::Instrument;
+
+use crate::common::{browser, helpers};
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("{step}: {element} not found within {timeout:?} (tried {selectors})")]
+    NotFound {
+        step: &'static str,
+        element: &'static str,
+        selectors: String,
+        timeout: Duration,
+    },
+    #[error("{step}: {element} still shown after {timeout:?}")]
+    StillPresent {
+        step: &'static str,
+        element: &'static str,
+        timeout: Duration,
+    },
+    #[error("{step}: WebDriver command failed")]
+    Command {
+        step: &'static str,
+        // Boxed, as WebDriver errors are rather large
+        #[source]
+        source: Box<tf::error::WebDriverError>,
+    },
+}
+
+/// How long to keep looking for elements before giving up, and how often to look
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct Waits {
+    #[serde(rename = "timeout_ms", with = "helpers::milliseconds")]
+    pub timeout: Duration,
+    #[serde(rename = "interval_ms", with = "helpers::milliseconds")]
+    pub interval: Duration,
+}
+
+impl Default for Waits {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            interval: Duration::from_millis(250),
+        }
+    }
+}
+
+/// Element on a page, with selectors tried in order. Keeping a few alternatives around means that a change to the
+/// page only breaks the flow once all of them stop matching.
+#[derive(Clone, Debug)]
+pub struct Element {
+    /// What the element is, for errors and logs, e.g. "e-mail field"
+    pub name: &'static str,
+    pub selectors: Vec<tf::By>,
+}
+
+impl Element {
+    pub fn new(name: &'static str, selectors: impl IntoIterator<Item = tf::By>) -> Self {
+        Self {
+            name,
+            selectors: selectors.into_iter().collect(),
+        }
+    }
+
+    fn describe_selectors(&self) -> String {
+        self.selectors
+            .iter()
+            .map(|selector| selector.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Browser session driven step by step. Every step has a name, which ends up in logs and errors.
+#[derive(Clone)]
+pub struct Session {
+    driver: tf::WebDriver,
+    waits: Waits,
+    headless: bool,
+}
+
+impl Session {
+    pub fn new(driver: tf::WebDriver, waits: Waits, headless: bool) -> Self {
+        Self {
+            driver,
+            waits,
+            headless,
+        }
+    }
+
+    /// The underlying WebDriver, for anything not covered here
+    pub fn driver(&self) -> &tf::WebDriver {
+        &self.driver
+    }
+
+    pub fn waits(&self) -> Waits {
+        self.waits
+    }
+
+    /// Whether the browser window is hidden, so the user can't solve anything in it
+    pub fn is_headless(&self) -> bool {
+        self.headless
+    }
+
+    /// Runs a single WebDriver command as `step`
+    pub async fn step<T>(
+        &self,
+        step: &'static str,
+        command: impl Future<Output = tf::error::WebDriverResult<T>>,
+    ) -> Result<T, Error> {
+        command
+            .instrument(browser::command_span(step))
+            .await
+            .map_err(|source| Error::Command {
+                step,
+                source: Box::new(source),
+            })
+    }
+
+    pub async fn goto(&self, step: &'static str, url: &url::Url) -> Result<(), Error> {
+        self.step(step, self.driver.goto(url.as_str())).await
+    }
+
+    /// Looks for `element` once, without waiting for it
+    pub async fn find_now(
+        &self,
+        step: &'static str,
+        element: &Element,
+    ) -> Result<Option<tf::WebElement>, Error> {
+        for selector in &element.selectors {
+            let found = self
+                .step(step, self.driver.find_all(selector.clone()))
+                .await?;
+            if let Some(found) = found.into_iter().next() {
+                return Ok(Some(found));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Waits for `element` to show up
+    pub async fn find(
+        &self,
+        step: &'static str,
+        element: &Element,
+    ) -> Result<tf::WebElement, Error> {
+        self.find_within(step, element, self.waits.timeout).await
+    }
+
+    /// Waits for `element` to show up, for at most `timeout`
+    pub async fn find_within(
+        &self,
+        step: &'static str,
+        element: &Element,
+        timeout: Duration,
+    ) -> Result<tf::WebElement, Error> {
+        self.first_of(step, std::slice::from_ref(element), timeout)
+            .await?
+            .map(|(_, found)| found)
+            .ok_or_else(|| Error::NotFound {
+                step,
+                element: element.name,
+                selectors: element.describe_selectors(),
+                timeout,
+            })
+    }
+
+    /// Waits for any of `elements` to show up, for at most `timeout`. Returns the index of the element found first,
+    /// along with it.
+    pub async fn first_of(
+        &self,
+        step: &'static str,
+        elements: &[Element],
+        timeout: Duration,
+    ) -> Result<Option<(usize, tf::WebElement)>, Error> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            for (index, element) in elements.iter().enumerate() {
+                if let Some(found) = self.find_now(step, element).await? {
+                    return Ok(Some((index, found)));
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Ok(None);
+            }
+            tokio::time::sleep(self.waits.interval).await;
+        }
+    }
+
+    /// Waits for `element` to show up and clicks it. The element is returned, so that it can be waited on to go away.
+    pub async fn click(
+        &self,
+        step: &'static str,
+        element: &Element,
+    ) -> Result<tf::WebElement, Error> {
+        let found = self.find(step, element).await?;
+        self.step(step, found.click()).await?;
+        Ok(found)
+    }
+
+    /// Waits for `element` to show up and types `text` into it, replacing what was there
+    pub async fn type_into(
+        &self,
+        step: &'static str,
+        element: &Element,
+        text: &str,
+    ) -> Result<(), Error> {
+        let found = self.find(step, element).await?;
+        self.step(step, found.clear()).await?;
+        self.step(step, found.send_keys(text)).await
+    }
+
+    /// Waits for `found`, an instance of `element`, to go away, e.g. after submitting a form
+    pub async fn wait_until_gone(
+        &self,
+        step: &'static str,
+        element: &Element,
+        found: &tf::WebElement,
+    ) -> Result<(), Error> {
+        let deadline = tokio::time::Instant::now() + self.waits.timeout;
+        while self.step(step, found.is_present()).await? {
+            if tokio::time::Instant::now() >= deadline {
+                return Err(Error::StillPresent {
+                    step,
+                    element: element.name,
+                    timeout: self.waits.timeout,
+                });
+            }
+            tokio::time::sleep(self.waits.interval).await;
+        }
+        Ok(())
+    }
+}
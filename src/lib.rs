@@ -2,6 +2,7 @@
 pub mod backend;
 pub mod common;
 pub mod scene;
+pub mod source;
 
 #[derive(Debug)]
 pub enum Message {
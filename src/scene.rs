@@ -1,29 +1,50 @@
-pub mod goodreads;
+pub mod diagnostics;
+pub mod error;
+pub mod home;
 pub mod launch;
+pub mod settings;
+pub mod welcome;
 
-use crate::backend;
+use crate::{
+    backend,
+    common::{config::Config, http},
+};
 use color_eyre::Result;
-use iced::{Element, Task};
+use iced::{Element, Subscription, Task};
 
 type Error = backend::Error;
 
 #[derive(Clone)]
 pub enum State {
     Launch(launch::Launch),
-    Goodreads(goodreads::State),
+    Settings(settings::Settings),
+    Welcome(welcome::Welcome),
+    Home(home::Home),
 }
 
-impl Default for State {
-    fn default() -> Self {
-        Self::Launch(launch::Launch::default())
+impl State {
+    /// Short name for the diagnostics panel
+    pub fn name(&self) -> &'static str {
+        match self {
+            State::Launch(_) => "Launch",
+            State::Settings(_) => "Settings",
+            State::Welcome(_) => "Welcome",
+            State::Home(_) => "Home",
+        }
     }
 }
 
 #[derive(Debug, Clone)]
 pub enum Message {
     Connected,
+    /// The backend stopped a job on request
+    Cancelled,
+    DiagnosticsToggled,
+    Diagnostics(diagnostics::Message),
     Launch(launch::Message),
-    Goodreads(goodreads::Message),
+    Settings(settings::Message),
+    Welcome(welcome::Message),
+    Home(home::Message),
 }
 
 impl From<Message> for crate::Message {
@@ -35,40 +56,147 @@ impl From<Message> for crate::Message {
 impl From<crate::backend::Output> for Message {
     fn from(output: crate::backend::Output) -> Self {
         match output {
+            backend::Output::Cancelled => Self::Cancelled,
             backend::Output::Connection(connection) => Self::Connected,
-            backend::Output::Goodreads(output) => Self::Goodreads(output.into()),
             backend::Output::Uninitialized(output) => Self::Launch(output.into()),
+            backend::Output::Welcome(output) => Self::Welcome(output.into()),
+            backend::Output::Home(output) => Self::Home(output.into()),
         }
     }
 }
 
-#[derive(Default)]
 pub struct Scene {
     state: State,
+    /// Kept up to date with the scenes that change it, for things shared by all scenes, like the theme
+    config: Config,
+    /// Shown below the current scene while open
+    diagnostics: Option<diagnostics::Diagnostics>,
 }
 
 impl Scene {
+    /// Starts at the launch screen. A config that failed to load is replaced by the defaults, and the error shown.
+    pub fn new(config: Result<Config>) -> Self {
+        let (config, error) = match config {
+            Ok(config) => (config, None),
+            Err(error) => (Config::default(), Some(error::Report::new(error.as_ref()))),
+        };
+        http::configure(config.requests.clone());
+
+        let mut launch = launch::Launch::new(config.clone());
+        if let Some(error) = error {
+            launch = launch.with_error(error);
+        }
+
+        Self {
+            state: State::Launch(launch),
+            config,
+            diagnostics: None,
+        }
+    }
+
+    pub fn theme(&self) -> iced::Theme {
+        self.config.theme()
+    }
+
     pub fn update(
         &mut self,
         message: Result<Message, Error>,
     ) -> (Option<backend::Input>, Task<crate::Message>) {
+        // The diagnostics panel sits on top of whatever scene is shown, so it's handled here
+        match message {
+            Ok(Message::DiagnosticsToggled) => {
+                self.diagnostics = match self.diagnostics {
+                    Some(_) => None,
+                    None => Some(Default::default()),
+                };
+                return (None, Task::none());
+            }
+            Ok(Message::Diagnostics(message)) => {
+                let task = match &mut self.diagnostics {
+                    Some(diagnostics) => {
+                        diagnostics.update(message, self.state.name(), &self.config)
+                    }
+                    None => Task::none(),
+                };
+                return (
+                    None,
+                    task.map(|message| Message::Diagnostics(message).into()),
+                );
+            }
+            _ => (),
+        }
+
         let (state, output, task) = match self.state.clone() {
             State::Launch(state) => {
                 state.update(message.and_then(|message| launch::Message::try_from(message)))
             }
-            State::Goodreads(state) => {
-                state.update(message.and_then(|message| goodreads::Message::try_from(message)))
-            }
+            State::Settings(state) => state.update(message.and_then(settings::Message::try_from)),
+            State::Welcome(state) => state.update(message.and_then(welcome::Message::try_from)),
+            State::Home(state) => state.update(message.and_then(home::Message::try_from)),
         };
 
         self.state = state;
+        match &self.state {
+            State::Launch(state) => self.config = state.config().clone(),
+            State::Settings(state) => self.config = state.config().clone(),
+            State::Welcome(_) | State::Home(_) => (),
+        }
+
         (output, task.map(|message| message.into()))
     }
 
+    pub fn subscription(&self) -> Subscription<Message> {
+        let scene = match &self.state {
+            State::Launch(_) => Subscription::none(),
+            State::Settings(state) => state.subscription().map(Message::Settings),
+            State::Welcome(_) => Subscription::none(),
+            State::Home(state) => state.subscription().map(Message::Home),
+        };
+
+        let diagnostics = match &self.diagnostics {
+            Some(diagnostics) => diagnostics.subscription().map(Message::Diagnostics),
+            None => Subscription::none(),
+        };
+
+        let toggle = iced::keyboard::on_key_press(|key, _modifiers| {
+            (key == iced::keyboard::Key::Named(iced::keyboard::key::Named::F12))
+                .then_some(Message::DiagnosticsToggled)
+        });
+
+        Subscription::batch([scene, diagnostics, toggle])
+    }
+
     pub fn view(&self) -> Element<Message> {
-        match &self.state {
+        let scene = match &self.state {
             State::Launch(state) => state.view().map(Message::Launch),
-            State::Goodreads(state) => state.view().map(Message::Goodreads),
-        }
+            State::Settings(state) => state.view().map(Message::Settings),
+            State::Welcome(state) => state.view().map(Message::Welcome),
+            State::Home(state) => state.view().map(Message::Home),
+        };
+
+        let diagnostics = self.diagnostics.as_ref().map(|diagnostics| {
+            diagnostics
+                .view(self.state.name())
+                .map(Message::Diagnostics)
+        });
+
+        let toggle = iced::widget::row![
+            iced::widget::horizontal_space(),
+            iced::widget::button(
+                iced::widget::text(if self.diagnostics.is_some() {
+                    "Hide diagnostics (F12)"
+                } else {
+                    "Diagnostics (F12)"
+                })
+                .size(12)
+            )
+            .style(iced::widget::button::text)
+            .on_press(Message::DiagnosticsToggled),
+        ];
+
+        iced::widget::column![iced::widget::container(scene).height(iced::Length::Fill)]
+            .push(toggle)
+            .push_maybe(diagnostics)
+            .into()
     }
 }
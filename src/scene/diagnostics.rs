@@ -0,0 +1,138 @@
+use std::str::FromStr;
+
+use iced::Task;
+
+use crate::common::{config::Config, diagnostics, logging};
+
+/// How often the panel picks up new log lines and requests while it is open
+const REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_millis(500);
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    LevelSelected(String),
+    Refresh,
+    CopyClick,
+}
+
+/// Panel showing what the backend is up to, shown below the current scene
+#[derive(Clone, Debug)]
+pub struct Diagnostics {
+    /// Least severe level shown
+    level: tracing::Level,
+    snapshot: diagnostics::Snapshot,
+    copied: bool,
+}
+
+impl Default for Diagnostics {
+    fn default() -> Self {
+        Self {
+            level: tracing::Level::INFO,
+            snapshot: diagnostics::snapshot(),
+            copied: false,
+        }
+    }
+}
+
+impl Diagnostics {
+    pub fn update(
+        &mut self,
+        message: Message,
+        scene_state: &str,
+        config: &Config,
+    ) -> Task<Message> {
+        match message {
+            Message::LevelSelected(level) => {
+                if let Ok(level) = tracing::Level::from_str(&level) {
+                    self.level = level;
+                }
+            }
+            Message::Refresh => self.snapshot = diagnostics::snapshot(),
+            Message::CopyClick => {
+                self.snapshot = diagnostics::snapshot();
+                self.copied = true;
+                return iced::clipboard::write(diagnostics::bundle(
+                    &self.snapshot,
+                    scene_state,
+                    config,
+                ));
+            }
+        }
+
+        Task::none()
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        iced::time::every(REFRESH_INTERVAL).map(|_| Message::Refresh)
+    }
+
+    pub fn view(&self, scene_state: &str) -> iced::Element<Message> {
+        let status = iced::widget::column![
+            iced::widget::text(format!("Scene: {scene_state}")),
+            iced::widget::text(format!(
+                "Backend: {}",
+                self.snapshot.backend_state.unwrap_or("Not started")
+            )),
+            iced::widget::text(format!("Driver: {}", self.snapshot.driver)),
+        ]
+        .spacing(2)
+        .width(iced::Length::Fill);
+
+        let controls = iced::widget::row![
+            iced::widget::pick_list(
+                logging::LEVELS.map(String::from).to_vec(),
+                Some(self.level.to_string().to_lowercase()),
+                Message::LevelSelected,
+            ),
+            iced::widget::button(if self.copied { "Copied" } else { "Copy bundle" })
+                .on_press(Message::CopyClick),
+        ]
+        .spacing(5);
+
+        let requests = iced::widget::column(
+            self.snapshot
+                .requests
+                .iter()
+                .rev()
+                .map(|request| iced::widget::text(request.to_string()).size(12).into()),
+        );
+
+        // Newest first, so the latest lines are visible without scrolling
+        let log = iced::widget::column(
+            self.snapshot
+                .log
+                .iter()
+                .rev()
+                .filter(|line| line.level <= self.level)
+                .map(|line| {
+                    iced::widget::text(line.to_string())
+                        .size(12)
+                        .font(iced::Font::MONOSPACE)
+                        .into()
+                }),
+        );
+
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::row![status, controls].spacing(10),
+                iced::widget::row![
+                    iced::widget::column![
+                        iced::widget::text("Recent requests").size(16),
+                        iced::widget::scrollable(requests).height(iced::Length::Fill),
+                    ]
+                    .width(iced::Length::FillPortion(1)),
+                    iced::widget::column![
+                        iced::widget::text("Log").size(16),
+                        iced::widget::scrollable(log).height(iced::Length::Fill),
+                    ]
+                    .width(iced::Length::FillPortion(2)),
+                ]
+                .spacing(10),
+            ]
+            .spacing(10),
+        )
+        .padding(10)
+        .height(250)
+        .style(iced::widget::container::rounded_box)
+        .into()
+    }
+}
@@ -0,0 +1,84 @@
+use iced::Element;
+
+/// An error as shown to the user: What went wrong, followed by the chain of causes that led to it
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub summary: String,
+    pub causes: Vec<String>,
+}
+
+impl Report {
+    pub fn new(error: &(dyn std::error::Error + 'static)) -> Self {
+        let summary = error.to_string();
+        let mut causes: Vec<String> = vec![];
+        let mut source = error.source();
+        while let Some(error) = source {
+            let cause = error.to_string();
+            // Wrapping errors often repeat the message of their source
+            if cause != summary && causes.last() != Some(&cause) {
+                causes.push(cause);
+            }
+            source = error.source();
+        }
+
+        Self { summary, causes }
+    }
+}
+
+impl From<&crate::backend::Error> for Report {
+    fn from(error: &crate::backend::Error) -> Self {
+        Self::new(error)
+    }
+}
+
+impl std::fmt::Display for Report {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.summary)?;
+        for cause in &self.causes {
+            write!(f, "\n  Caused by: {cause}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Dismissible banner showing an error, optionally offering to retry whatever failed
+pub fn banner<'a, Message: Clone + 'a>(
+    report: &'a Report,
+    on_dismiss: Message,
+    on_retry: Option<Message>,
+) -> Element<'a, Message> {
+    let causes = report.causes.iter().map(|cause| {
+        iced::widget::text(format!("Caused by: {cause}"))
+            .size(12)
+            .into()
+    });
+
+    let message = iced::widget::column![iced::widget::text(&report.summary)]
+        .extend(causes)
+        .spacing(2)
+        .width(iced::Length::Fill);
+
+    let controls = iced::widget::row![]
+        .push_maybe(on_retry.map(|retry| iced::widget::button("Retry").on_press(retry)))
+        .push(
+            iced::widget::button("Dismiss")
+                .style(iced::widget::button::secondary)
+                .on_press(on_dismiss),
+        )
+        .spacing(5);
+
+    iced::widget::container(
+        iced::widget::row![message, controls]
+            .spacing(10)
+            .align_y(iced::Alignment::Center),
+    )
+    .padding(10)
+    .width(iced::Length::Fill)
+    .style(|theme: &iced::Theme| {
+        let palette = theme.extended_palette();
+        iced::widget::container::Style::default()
+            .background(palette.danger.weak.color)
+            .color(palette.danger.weak.text)
+    })
+    .into()
+}
@@ -0,0 +1,1188 @@
+use crate::{
+    backend,
+    common::config::Config,
+    common::http,
+    common::keybindings::{self, Action, KeyBindings},
+    common::sorting::{self, Conflict, Constraints, Direction, Preference, Rule, Session, SortKey},
+    scene::{
+        self, State,
+        error::{self, Report},
+    },
+    source::{self, Details, FetchError, Item, Source},
+};
+
+use chrono::Datelike;
+use color_eyre::Result;
+use iced::{
+    Task,
+    futures::{SinkExt, Stream, StreamExt},
+    widget::{image::Handle, scrollable},
+};
+use std::{cmp::Ordering, collections::VecDeque};
+use strum::IntoEnumIterator;
+
+/// Shown until the image of an item has been downloaded
+pub const COVER_PLACEHOLDER_DATA: &[u8] =
+    include_bytes!(r"..\..\Assets\Icons\cover_placeholder.jpg");
+
+/// Number of covers per page of the grid
+const GRID_PAGE_SIZE: usize = 30;
+
+/// Number of recent answers the answering speed is averaged over
+const ANSWER_TIME_SAMPLES: usize = 20;
+
+/// Answers taking longer than this are most likely interrupted by a break, and don't reflect answering speed
+const MAX_ANSWER_TIME: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Number of characters of a description shown before it is expanded
+const DESCRIPTION_PREVIEW_LENGTH: usize = 400;
+
+/// Frame colors distinguishing groups of items linked by constraints
+const GROUP_COLORS: [iced::Color; 6] = [
+    iced::Color::from_rgb(0.80, 0.29, 0.09),
+    iced::Color::from_rgb(0.15, 0.55, 0.82),
+    iced::Color::from_rgb(0.52, 0.60, 0.00),
+    iced::Color::from_rgb(0.83, 0.21, 0.51),
+    iced::Color::from_rgb(0.16, 0.63, 0.60),
+    iced::Color::from_rgb(0.42, 0.44, 0.77),
+];
+
+#[derive(Clone, Debug)]
+pub struct Home {
+    source: &'static dyn Source,
+    items: Vec<Item>,
+    details: Vec<Option<Result<Details, FetchError>>>,
+    images: Vec<Option<Handle>>,
+    selected_item: Option<usize>,
+    sort_rules: Vec<Rule>,
+    session: Session,
+    pin_position_input: String,
+    precedence_target: Option<ItemOption>,
+    move_position_input: String,
+    /// Item being dragged in the grid, and the item it would be dropped onto
+    drag: Option<(usize, Option<usize>)>,
+    grid_page: usize,
+    description_expanded: bool,
+    key_bindings: KeyBindings,
+    /// When the current comparison was first shown, and how long the recent ones took to answer
+    comparison_shown: Option<std::time::Instant>,
+    answer_times: VecDeque<std::time::Duration>,
+    /// Downloads still running, which can be cancelled
+    downloads: Vec<iced::task::Handle>,
+    image_cache: std::path::PathBuf,
+    /// Order last written back to the source, to tell whether there is anything to save
+    saved_order: Vec<usize>,
+    saving: bool,
+    /// Whether the default sorting was applied, which happens once the first download has finished
+    sorted_by_default: bool,
+    error: Option<Report>,
+}
+
+/// Entry in a list for picking an item
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemOption {
+    index: usize,
+    title: String,
+}
+
+impl std::fmt::Display for ItemOption {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.title)
+    }
+}
+
+impl From<Home> for State {
+    fn from(state: Home) -> Self {
+        Self::Home(state)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    DetailsFetched((usize, Box<Result<Details, FetchError>>)),
+    ItemSelected(usize),
+    SortRuleAdded,
+    SortRuleRemoved(usize),
+    SortKeySelected(usize, SortKey),
+    SortDirectionSelected(usize, Direction),
+    AutoSort,
+    ComparisonStarted,
+    ComparisonAnswered(Preference),
+    PinPositionInput(String),
+    Pinned,
+    Unpinned(usize),
+    PrecedenceTargetSelected(ItemOption),
+    PrecedenceAdded,
+    PrecedenceRemoved(usize, usize),
+    DragStarted(usize),
+    DragHovered(usize),
+    Dropped(usize),
+    DragEnded,
+    MovedToTop,
+    MovedToBottom,
+    MovePositionInput(String),
+    MovedToPosition,
+    KeyPressed(iced::keyboard::Key),
+    ComparisonSkipped,
+    TiesRefined,
+    Undone,
+    DescriptionToggled,
+    GridPageSelected(usize),
+    ErrorDismissed,
+    ItemRetried(usize),
+    FailedItemsRetried,
+    DownloadCancelled,
+    SaveClick,
+    OrderApplied,
+}
+
+impl From<Message> for scene::Message {
+    fn from(message: Message) -> Self {
+        Self::Home(message)
+    }
+}
+
+impl From<backend::home::Output> for Message {
+    fn from(output: backend::home::Output) -> Self {
+        match output {
+            backend::home::Output::OrderApplied => Self::OrderApplied,
+        }
+    }
+}
+
+impl TryFrom<scene::Message> for Message {
+    type Error = crate::backend::Error;
+
+    fn try_from(message: scene::Message) -> Result<Self, Self::Error> {
+        match message {
+            scene::Message::Home(message) => Ok(message),
+            _ => Err(Self::Error::InvalidState {
+                state: "Home".into(),
+                message: format!("{:?}", message),
+            }),
+        }
+    }
+}
+
+impl Home {
+    pub fn new(items: Vec<Item>, source: &'static dyn Source, config: &Config) -> Self {
+        // The default sorting may be for another source
+        let default_sorting = if source.sort_keys().contains(&config.default_sorting.key) {
+            config.default_sorting
+        } else {
+            Rule::new(source.sort_keys()[0], Direction::Ascending)
+        };
+        let session = Session::new(items.len());
+
+        Self {
+            source,
+            details: vec![None; items.len()],
+            images: vec![None; items.len()],
+            items,
+            selected_item: None,
+            sort_rules: vec![default_sorting],
+            saved_order: session.order().to_vec(),
+            session,
+            pin_position_input: String::new(),
+            precedence_target: None,
+            move_position_input: String::new(),
+            drag: None,
+            grid_page: 0,
+            description_expanded: false,
+            key_bindings: config.key_bindings.clone(),
+            comparison_shown: None,
+            answer_times: VecDeque::new(),
+            downloads: Vec::new(),
+            image_cache: config.cache_dir.clone(),
+            saving: false,
+            sorted_by_default: false,
+            error: None,
+        }
+    }
+
+    pub fn update(
+        mut self,
+        message: Result<Message, crate::backend::Error>,
+    ) -> (State, Option<crate::backend::Input>, Task<scene::Message>) {
+        let mut output: Option<crate::backend::home::Input> = None;
+        let mut task = Task::none();
+
+        match message {
+            Ok(message) => match message {
+                Message::DetailsFetched((i, details)) => {
+                    let mut details = *details;
+                    // Only the handle is kept of the image, so the data isn't held twice
+                    if let Ok(details) = &mut details
+                        && let Some(image) = details.image.take()
+                    {
+                        self.images[i] = Some(Handle::from_bytes(image.0));
+                    }
+                    // Failures are shown on the tile of the item, and summed up next to the grid pages
+                    if let Err(ref error) = details {
+                        tracing::warn!(item = self.items[i].id, "Failed to fetch item: {error}");
+                    }
+
+                    self.details[i] = Some(details);
+                    if !self.is_downloading() {
+                        self.downloads.clear();
+                    }
+
+                    // Every fetched item may reveal series membership
+                    let constraints = self.series_constraints();
+                    self.session.set_derived_constraints(constraints);
+
+                    // Most sort keys only have values once the items are fetched. Whatever the user ordered
+                    // in the meantime is left alone.
+                    if !self.is_downloading()
+                        && !std::mem::replace(&mut self.sorted_by_default, true)
+                        && !self.session.can_undo()
+                        && self.session.ranking().is_none()
+                    {
+                        return self.update(Ok(Message::AutoSort));
+                    }
+                }
+                Message::ItemSelected(selection) => {
+                    self.selected_item = Some(selection);
+                    self.precedence_target = None;
+                    self.move_position_input = self
+                        .position(selection)
+                        .map(|position| (position + 1).to_string())
+                        .unwrap_or_default();
+                    self.pin_position_input = self
+                        .session
+                        .manual_constraints()
+                        .pinned_position(selection)
+                        .map(|position| (position + 1).to_string())
+                        .unwrap_or_default();
+                }
+                Message::SortRuleAdded => self
+                    .sort_rules
+                    .push(Rule::new(SortKey::Title, Direction::Ascending)),
+                Message::SortRuleRemoved(i) => {
+                    self.sort_rules.remove(i);
+                }
+                Message::SortKeySelected(i, key) => self.sort_rules[i].key = key,
+                Message::SortDirectionSelected(i, direction) => {
+                    self.sort_rules[i].direction = direction
+                }
+                Message::AutoSort => {
+                    let mut session = std::mem::take(&mut self.session);
+                    session.auto_sort(&self.sort_rules, |i, key| self.sort_value(i, key));
+                    self.session = session;
+                }
+                Message::ComparisonStarted => {
+                    self.session.start_comparisons();
+                    self.comparison_shown = Some(std::time::Instant::now());
+                }
+                Message::ComparisonAnswered(preference) => {
+                    if self.session.is_comparing() {
+                        self.record_answer_time();
+                    }
+                    self.session.answer(preference);
+                }
+                Message::PinPositionInput(position) => self.pin_position_input = position,
+                Message::Pinned => {
+                    // Positions are shown starting at 1
+                    if let Some(i) = self.selected_item
+                        && let Ok(position) = self.pin_position_input.trim().parse::<usize>()
+                        && position > 0
+                    {
+                        self.session.pin(i, position - 1);
+                    }
+                }
+                Message::Unpinned(i) => {
+                    self.session.unpin(i);
+                    self.pin_position_input.clear();
+                }
+                Message::PrecedenceTargetSelected(item) => self.precedence_target = Some(item),
+                Message::PrecedenceAdded => {
+                    if let Some(i) = self.selected_item
+                        && let Some(target) = self.precedence_target.take()
+                        && target.index != i
+                    {
+                        self.session.add_precedence(i, target.index);
+                    }
+                }
+                Message::PrecedenceRemoved(earlier, later) => {
+                    self.session.remove_precedence(earlier, later)
+                }
+                Message::DragStarted(i) => {
+                    self.drag = Some((i, None));
+                    return self.update(Ok(Message::ItemSelected(i)));
+                }
+                Message::DragHovered(target) => {
+                    if let Some((_, hovered)) = &mut self.drag {
+                        *hovered = Some(target)
+                    }
+                }
+                Message::Dropped(target) => {
+                    // Dropping an item onto another one takes over its place
+                    if let Some((i, _)) = self.drag.take()
+                        && i != target
+                        && let Some(position) = self.position(target)
+                    {
+                        self.session.move_item(i, position);
+                    }
+                }
+                Message::DragEnded => self.drag = None,
+                Message::MovedToTop => {
+                    if let Some(i) = self.selected_item {
+                        self.session.move_item(i, 0)
+                    }
+                }
+                Message::MovedToBottom => {
+                    if let Some(i) = self.selected_item {
+                        self.session.move_item(i, self.items.len() - 1)
+                    }
+                }
+                Message::MovePositionInput(position) => self.move_position_input = position,
+                Message::MovedToPosition => {
+                    if let Some(i) = self.selected_item
+                        && let Ok(position) = self.move_position_input.trim().parse::<usize>()
+                        && position > 0
+                    {
+                        self.session.move_item(i, position - 1)
+                    }
+                }
+                Message::KeyPressed(key) => {
+                    if let Some(action) = self.key_bindings.action(&key) {
+                        let message = match action {
+                            Action::PickLeft => Message::ComparisonAnswered(Preference::First),
+                            Action::PickRight => Message::ComparisonAnswered(Preference::Second),
+                            Action::Tie => Message::ComparisonAnswered(Preference::Tie),
+                            Action::Skip => Message::ComparisonSkipped,
+                            Action::Undo => Message::Undone,
+                            Action::ToggleBlurb => Message::DescriptionToggled,
+                        };
+                        return self.update(Ok(message));
+                    } else if let Some(digit) = keybindings::digit(&key) {
+                        // "1" is the first page, "0" the tenth
+                        let page = (digit as usize + 9) % 10;
+                        return self.update(Ok(Message::GridPageSelected(page)));
+                    }
+                }
+                Message::ComparisonSkipped => {
+                    // The key is there even when the button is disabled
+                    if self.session.can_skip() {
+                        self.session.skip();
+                        self.comparison_shown = Some(std::time::Instant::now());
+                    }
+                }
+                Message::TiesRefined => {
+                    self.session.refine_ties();
+                }
+                Message::Undone => {
+                    self.session.undo();
+                    self.comparison_shown = Some(std::time::Instant::now());
+                }
+                Message::DescriptionToggled => {
+                    self.description_expanded = !self.description_expanded
+                }
+                Message::GridPageSelected(page) => {
+                    if page < self.grid_page_count() {
+                        self.grid_page = page
+                    }
+                }
+                Message::ErrorDismissed => self.error = None,
+                Message::ItemRetried(i) => task = self.fetch(vec![i]),
+                Message::FailedItemsRetried => {
+                    let failed = (0..self.details.len())
+                        .filter(|&i| matches!(self.details[i], Some(Err(_))))
+                        .collect();
+                    task = self.fetch(failed);
+                }
+                Message::DownloadCancelled => {
+                    for download in self.downloads.drain(..) {
+                        download.abort();
+                    }
+                    // Items that never arrived can be retried like failed ones
+                    for details in &mut self.details {
+                        if details.is_none() {
+                            *details = Some(Err(FetchError::Cancelled));
+                        }
+                    }
+                }
+                Message::SaveClick => {
+                    self.error = None;
+                    self.saving = true;
+                    output = Some(crate::backend::home::Input::ApplyOrder(
+                        self.session
+                            .order()
+                            .iter()
+                            .map(|&i| self.items[i].id.clone())
+                            .collect(),
+                    ));
+                }
+                Message::OrderApplied => {
+                    self.saving = false;
+                    self.saved_order = self.session.order().to_vec();
+                }
+            },
+            Err(error) => {
+                self.saving = false;
+                self.error = Some(Report::from(&error));
+            }
+        }
+
+        (
+            self.into(),
+            output.map(|output| output.into()),
+            task.map(|message| message.into()),
+        )
+    }
+
+    /// Shows a problem that came up while opening the list
+    pub fn with_error(mut self, error: Option<Report>) -> Self {
+        self.error = error;
+        self
+    }
+
+    /// Fetches the details of every item on the list
+    pub fn fetch_all(&mut self) -> Task<Message> {
+        self.fetch((0..self.items.len()).collect())
+    }
+
+    /// Fetches the details of the given items, replacing whatever was fetched for them before
+    fn fetch(&mut self, items: Vec<usize>) -> Task<Message> {
+        for &i in &items {
+            self.details[i] = None;
+        }
+        let items = items
+            .into_iter()
+            .map(|i| (i, self.items[i].clone()))
+            .collect();
+        let (task, download) = Task::run(
+            fetch_items(self.source, items, self.image_cache.clone()),
+            std::convert::identity,
+        )
+        .abortable();
+        self.downloads.push(download);
+        task
+    }
+
+    /// Stands in for the cover of an item that couldn't be fetched, showing why
+    fn error_tile(&self, i: usize, error: &FetchError) -> iced::Element<Message> {
+        iced::widget::container(
+            iced::widget::column![
+                iced::widget::text(&self.items[i].title).size(12),
+                iced::widget::text(error.to_string())
+                    .size(10)
+                    .style(iced::widget::text::danger),
+                iced::widget::vertical_space(),
+                iced::widget::button(iced::widget::text("Retry").size(12))
+                    .on_press(Message::ItemRetried(i)),
+            ]
+            .spacing(4),
+        )
+        .padding(4)
+        .height(150)
+        .clip(true)
+        .style(iced::widget::container::rounded_box)
+        .into()
+    }
+
+    pub fn view(&self) -> iced::Element<Message> {
+        /*******************
+         * Item comparison *
+         *******************/
+        let comparisons = match self
+            .session
+            .ranking()
+            .and_then(|ranking| ranking.comparison())
+        {
+            Some((first, second)) => iced::widget::row![
+                self.item_choice(first, Preference::First),
+                iced::widget::column![
+                    iced::widget::button("Can't decide")
+                        .on_press(Message::ComparisonAnswered(Preference::Tie)),
+                    iced::widget::button("Skip")
+                        .style(iced::widget::button::secondary)
+                        .on_press_maybe(
+                            self.session
+                                .can_skip()
+                                .then_some(Message::ComparisonSkipped)
+                        ),
+                ]
+                .spacing(5)
+                .align_x(iced::Alignment::Center),
+                self.item_choice(second, Preference::Second)
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center),
+            None => iced::widget::row![
+                self.item_comparison(self.selected_item),
+                self.item_comparison(None)
+            ],
+        };
+
+        /*****************
+         * Grid of items *
+         *****************/
+        let constraints = self.session.constraints();
+        let groups = constraints.groups();
+        let covers: Vec<_> = self
+            .session
+            .order()
+            .iter()
+            .skip(self.grid_page * GRID_PAGE_SIZE)
+            .take(GRID_PAGE_SIZE)
+            .map(|&i| {
+                let cover = match &self.details[i] {
+                    Some(Err(error)) => self.error_tile(i, error),
+                    _ => iced::widget::image(self.image(Some(i))).into(),
+                };
+                (i, cover)
+            })
+            .collect();
+
+        let mut covers: Vec<_> = covers
+            .into_iter()
+            .map(|(i, cover)| {
+                // Covers are picked up on press and dropped onto another cover on release, while a plain click
+                // just selects them
+                let highlighted = match self.drag {
+                    Some((dragged, hovered)) => dragged == i || hovered == Some(i),
+                    None => self.selected_item == Some(i),
+                };
+                let cover = iced::widget::mouse_area(
+                    iced::widget::container(iced::widget::stack![
+                        cover,
+                        self.constraint_badge(i, &constraints)
+                    ])
+                    .padding(2)
+                    .style(move |theme: &iced::Theme| {
+                        if highlighted {
+                            iced::widget::container::Style::default()
+                                .background(theme.extended_palette().primary.base.color)
+                        } else {
+                            iced::widget::container::Style::default()
+                        }
+                    }),
+                )
+                .on_press(Message::DragStarted(i))
+                .on_enter(Message::DragHovered(i))
+                .on_release(Message::Dropped(i))
+                .interaction(iced::mouse::Interaction::Grab);
+                let cover = iced::widget::container(cover).width(iced::Length::Fixed(100.0));
+
+                // Items linked by constraints share a frame color, and name their series on hover
+                match groups.get(&i) {
+                    Some(&group) => {
+                        let color = GROUP_COLORS[group % GROUP_COLORS.len()];
+                        let cover = iced::widget::container(cover).padding(2).style(move |_| {
+                            iced::widget::container::Style {
+                                border: iced::Border {
+                                    color,
+                                    width: 2.0,
+                                    radius: 0.0.into(),
+                                },
+                                ..Default::default()
+                            }
+                        });
+                        match self.series_label(i) {
+                            Some(label) => iced::widget::tooltip(
+                                cover,
+                                iced::widget::container(iced::widget::text(label))
+                                    .padding(5)
+                                    .style(iced::widget::container::rounded_box),
+                                iced::widget::tooltip::Position::Bottom,
+                            )
+                            .into(),
+                            None => cover.into(),
+                        }
+                    }
+                    None => iced::Element::from(cover),
+                }
+            })
+            .collect();
+
+        let grid_height = 3;
+        let grid_spacing = 0;
+        let item_grid = iced::widget::mouse_area(
+            scrollable(
+                iced::widget::row({
+                    let mut columns = vec![];
+                    while grid_height <= covers.len() {
+                        columns.push(covers.drain(..grid_height).collect::<Vec<_>>());
+                    }
+                    columns.push(std::mem::take(&mut covers));
+
+                    columns
+                        .into_iter()
+                        .map(|covers| iced::widget::column(covers).spacing(grid_spacing).into())
+                })
+                .spacing(grid_spacing),
+            )
+            .direction(scrollable::Direction::Horizontal(
+                scrollable::Scrollbar::new(),
+            )),
+        )
+        // Releasing anywhere ends a drag. When released over a cover, that cover handles the drop first.
+        .on_release(Message::DragEnded);
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|report| error::banner(report, Message::ErrorDismissed, None));
+
+        iced::widget::column![]
+            .push_maybe(error)
+            .extend([
+                comparisons.into(),
+                self.key_cheat_sheet(),
+                self.progress(),
+                self.sorting_controls(),
+                self.move_controls(),
+                self.constraint_controls(),
+                self.download_progress(),
+                self.grid_pages(),
+                item_grid.into(),
+            ])
+            .spacing(10)
+            .into()
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        iced::keyboard::on_key_press(|key, _modifiers| Some(Message::KeyPressed(key)))
+    }
+
+    fn record_answer_time(&mut self) {
+        let now = std::time::Instant::now();
+        if let Some(shown) = self.comparison_shown.replace(now) {
+            self.answer_times
+                .push_back((now - shown).min(MAX_ANSWER_TIME));
+            if self.answer_times.len() > ANSWER_TIME_SAMPLES {
+                self.answer_times.pop_front();
+            }
+        }
+    }
+
+    /// Comparisons answered and left, with a progress bar and the time left at the current answering speed
+    fn progress(&self) -> iced::Element<Message> {
+        let Some(ranking) = self
+            .session
+            .ranking()
+            .filter(|ranking| !ranking.is_finished())
+        else {
+            return iced::widget::Space::new(0, 0).into();
+        };
+
+        let answered = ranking.comparisons();
+        let remaining = ranking.remaining_comparisons();
+        let mut status = format!("{answered} answered, about {remaining} left");
+        if !self.answer_times.is_empty() {
+            let average = self.answer_times.iter().sum::<std::time::Duration>()
+                / self.answer_times.len() as u32;
+            let time_left = (average * remaining as u32).as_secs();
+            status += &match time_left {
+                0..60 => " (less than a minute)".to_string(),
+                60..3600 => format!(" (~{} min)", time_left.div_ceil(60)),
+                _ => format!(" (~{}h {}min)", time_left / 3600, (time_left % 3600) / 60),
+            };
+        }
+
+        iced::widget::row![
+            iced::widget::progress_bar(0.0..=1.0, answered as f32 / (answered + remaining) as f32)
+                .width(200)
+                .height(10),
+            iced::widget::text(status),
+        ]
+        .spacing(10)
+        .padding(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn is_downloading(&self) -> bool {
+        self.details.iter().any(Option::is_none)
+    }
+
+    /// Items fetched so far, while a download is running
+    fn download_progress(&self) -> iced::Element<Message> {
+        if !self.is_downloading() {
+            return iced::widget::Space::new(0, 0).into();
+        }
+
+        let fetched = self
+            .details
+            .iter()
+            .filter(|details| details.is_some())
+            .count();
+        iced::widget::row![
+            iced::widget::progress_bar(0.0..=self.details.len() as f32, fetched as f32)
+                .width(200)
+                .height(10),
+            iced::widget::text(format!(
+                "Fetched {fetched}/{} {}",
+                self.details.len(),
+                self.source.terms().items
+            )),
+            iced::widget::button(iced::widget::text("Cancel download"))
+                .style(iced::widget::button::secondary)
+                .on_press(Message::DownloadCancelled),
+        ]
+        .spacing(10)
+        .padding(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    fn grid_page_count(&self) -> usize {
+        self.items.len().div_ceil(GRID_PAGE_SIZE).max(1)
+    }
+
+    fn grid_pages(&self) -> iced::Element<Message> {
+        let failed = self
+            .details
+            .iter()
+            .filter(|details| matches!(details, Some(Err(_))))
+            .count();
+        let retry = (failed > 0).then(|| {
+            iced::widget::button(iced::widget::text(format!("Retry {failed} failed")))
+                .style(iced::widget::button::danger)
+                .on_press(Message::FailedItemsRetried)
+        });
+
+        let pages = (0..self.grid_page_count()).map(|page| {
+            let button = iced::widget::button(iced::widget::text(page + 1));
+            if page == self.grid_page {
+                button.into()
+            } else {
+                button
+                    .style(iced::widget::button::secondary)
+                    .on_press(Message::GridPageSelected(page))
+                    .into()
+            }
+        });
+
+        iced::widget::row(pages)
+            .push_maybe(retry)
+            .spacing(2)
+            .padding(5)
+            .wrap()
+            .into()
+    }
+
+    /// Cheat sheet of the key bindings. They can be changed in the settings.
+    fn key_cheat_sheet(&self) -> iced::Element<Message> {
+        let cheat_sheet = Action::iter()
+            .map(|action| format!("{}: {action}", self.key_bindings.keys(action).join("/")))
+            .chain(std::iter::once("0-9: Grid page".to_string()))
+            .collect::<Vec<_>>()
+            .join("   ");
+
+        iced::widget::container(iced::widget::text(cheat_sheet).size(12))
+            .padding(5)
+            .into()
+    }
+
+    /// Position of item `i` in the current order
+    fn position(&self, i: usize) -> Option<usize> {
+        self.session.order().iter().position(|&other| other == i)
+    }
+
+    /// Manual reordering of the selected item
+    fn move_controls(&self) -> iced::Element<Message> {
+        if self.selected_item.is_none() {
+            return iced::widget::Space::new(0, 0).into();
+        }
+
+        iced::widget::row![
+            iced::widget::button("Move to top").on_press(Message::MovedToTop),
+            iced::widget::button("Move to bottom").on_press(Message::MovedToBottom),
+            iced::widget::text("Move to position"),
+            iced::widget::text_input("1", &self.move_position_input)
+                .on_input(Message::MovePositionInput)
+                .on_submit(Message::MovedToPosition)
+                .width(60),
+            iced::widget::button("Move").on_press(Message::MovedToPosition),
+        ]
+        .spacing(5)
+        .padding(5)
+        .align_y(iced::Alignment::Center)
+        .into()
+    }
+
+    /// Marks pinned items with their position, and items that have to come before others with an arrow
+    fn constraint_badge(&self, i: usize, constraints: &Constraints) -> iced::Element<Message> {
+        let mut badge = String::new();
+        if let Some(position) = constraints.pinned_position(i) {
+            badge += &format!("#{}", position + 1);
+        }
+        let before = self
+            .session
+            .manual_constraints()
+            .precedences()
+            .iter()
+            .filter(|(earlier, _)| *earlier == i)
+            .count();
+        if before > 0 {
+            badge += &format!(" >{before}");
+        }
+
+        if badge.is_empty() {
+            return iced::widget::Space::new(0, 0).into();
+        }
+
+        iced::widget::container(iced::widget::text(badge.trim().to_string()).size(12))
+            .padding([2, 4])
+            .style(iced::widget::container::rounded_box)
+            .into()
+    }
+
+    /// Pinning and precedence constraints for the selected item, and any conflicts between constraints
+    fn constraint_controls(&self) -> iced::Element<Message> {
+        let mut controls = iced::widget::column![].spacing(5).padding(5);
+
+        if let Some(i) = self.selected_item {
+            let manual = self.session.manual_constraints();
+
+            let mut pin = iced::widget::row![
+                iced::widget::text("Pin at position"),
+                iced::widget::text_input("1", &self.pin_position_input)
+                    .on_input(Message::PinPositionInput)
+                    .on_submit(Message::Pinned)
+                    .width(60),
+                iced::widget::button("Pin").on_press(Message::Pinned),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center);
+            if manual.pinned_position(i).is_some() {
+                pin = pin.push(iced::widget::button("Unpin").on_press(Message::Unpinned(i)));
+            }
+
+            let options: Vec<_> = self
+                .items
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != i)
+                .map(|(index, item)| ItemOption {
+                    index,
+                    title: item.title.clone(),
+                })
+                .collect();
+            let precedence = iced::widget::row![
+                iced::widget::text(self.source.terms().precedence),
+                iced::widget::pick_list(
+                    options,
+                    self.precedence_target.clone(),
+                    Message::PrecedenceTargetSelected
+                ),
+                iced::widget::button("Add").on_press(Message::PrecedenceAdded),
+            ]
+            .spacing(5)
+            .align_y(iced::Alignment::Center);
+
+            let precedences = manual
+                .precedences()
+                .iter()
+                .filter(|(earlier, later)| *earlier == i || *later == i)
+                .map(|&(earlier, later)| {
+                    iced::widget::row![
+                        iced::widget::text(format!(
+                            "{} before {}",
+                            self.items[earlier].title, self.items[later].title
+                        )),
+                        iced::widget::button("x")
+                            .on_press(Message::PrecedenceRemoved(earlier, later)),
+                    ]
+                    .spacing(5)
+                    .align_y(iced::Alignment::Center)
+                    .into()
+                });
+
+            controls = controls
+                .push(iced::widget::row![pin, precedence].spacing(20))
+                .push(iced::widget::column(precedences).spacing(2));
+        }
+
+        for conflict in self.session.conflicts() {
+            controls = controls.push(
+                iced::widget::text(format!("Unsatisfiable: {}", self.describe(&conflict)))
+                    .style(iced::widget::text::danger),
+            );
+        }
+
+        controls.into()
+    }
+
+    fn describe(&self, conflict: &Conflict) -> String {
+        let title = |i: usize| format!("\"{}\"", self.items[i].title);
+        let titles = |items: &[usize]| {
+            items
+                .iter()
+                .map(|&i| title(i))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+        match conflict {
+            Conflict::Cycle(items) => {
+                format!("{} would all have to come before each other", titles(items))
+            }
+            Conflict::SharedPosition { position, items } => {
+                format!("{} are all pinned to #{}", titles(items), position + 1)
+            }
+            Conflict::OutOfRange { item, position } => format!(
+                "{} is pinned to #{}, but there are only {} {}",
+                title(*item),
+                position + 1,
+                self.items.len(),
+                self.source.terms().items
+            ),
+            Conflict::Unsatisfiable { earlier, later } => format!(
+                "{} can't come before {} with the current pins",
+                title(*earlier),
+                title(*later)
+            ),
+        }
+    }
+
+    fn sorting_controls(&self) -> iced::Element<Message> {
+        let rules = self.sort_rules.iter().enumerate().map(|(i, rule)| {
+            let key = iced::widget::pick_list(
+                self.source
+                    .sort_keys()
+                    .iter()
+                    .map(|key| key.to_string())
+                    .collect::<Vec<_>>(),
+                Some(rule.key.to_string()),
+                move |selection| {
+                    Message::SortKeySelected(
+                        i,
+                        SortKey::try_from(selection.as_str()).expect("Invalid sort key selected!"),
+                    )
+                },
+            );
+            let direction = iced::widget::pick_list(
+                Direction::iter()
+                    .map(|direction| direction.to_string())
+                    .collect::<Vec<_>>(),
+                Some(rule.direction.to_string()),
+                move |selection| {
+                    Message::SortDirectionSelected(
+                        i,
+                        Direction::try_from(selection.as_str())
+                            .expect("Invalid sort direction selected!"),
+                    )
+                },
+            );
+            let remove = iced::widget::button("x").on_press(Message::SortRuleRemoved(i));
+            iced::widget::row![key, direction, remove].spacing(2).into()
+        });
+
+        let comparison_status = match self.session.ranking() {
+            Some(ranking) if ranking.is_finished() && ranking.ties() > 0 => {
+                iced::widget::text(format!(
+                    "Sorted with {} comparisons, {} groups of tied {} left in their current order",
+                    ranking.comparisons(),
+                    ranking.ties(),
+                    self.source.terms().items
+                ))
+            }
+            Some(ranking) if ranking.is_finished() => {
+                iced::widget::text(format!("Sorted with {} comparisons", ranking.comparisons()))
+            }
+            Some(ranking) => {
+                iced::widget::text(format!("Comparison {}", ranking.comparisons() + 1))
+            }
+            None => iced::widget::text(""),
+        };
+
+        let save = self.source.can_apply_order().then(|| {
+            let unsaved = self.session.order() != self.saved_order.as_slice();
+            iced::widget::button(iced::widget::text(if self.saving {
+                "Saving...".to_string()
+            } else {
+                format!("Save to {}", self.source.name())
+            }))
+            .on_press_maybe((unsaved && !self.saving).then_some(Message::SaveClick))
+        });
+
+        iced::widget::row(rules)
+            .push(iced::widget::button("+").on_press(Message::SortRuleAdded))
+            .push(iced::widget::button("Sort").on_press(Message::AutoSort))
+            .push(iced::widget::button("Compare").on_press(Message::ComparisonStarted))
+            .push(
+                iced::widget::button("Undo")
+                    .on_press_maybe(self.session.can_undo().then_some(Message::Undone)),
+            )
+            .push_maybe(
+                self.session
+                    .ranking()
+                    .is_some_and(|ranking| ranking.is_finished() && ranking.ties() > 0)
+                    .then(|| iced::widget::button("Refine ties").on_press(Message::TiesRefined)),
+            )
+            .push(comparison_status)
+            .push(iced::widget::horizontal_space())
+            .push_maybe(save)
+            .spacing(10)
+            .padding(5)
+            .align_y(iced::Alignment::Center)
+            .wrap()
+            .into()
+    }
+
+    /// One side of a comparison, with a button to prefer this item over the other one
+    fn item_choice(&self, i: usize, preference: Preference) -> iced::Element<Message> {
+        iced::widget::column![
+            self.item_comparison(Some(i)),
+            iced::widget::button(
+                iced::widget::container(self.source.terms().preference)
+                    .center_x(iced::Length::Fill)
+            )
+            .on_press(Message::ComparisonAnswered(preference))
+            .width(iced::Length::Fill)
+        ]
+        .width(iced::Length::FillPortion(1))
+        .into()
+    }
+
+    /// Details of item `i` if they have been fetched, or a placeholder carrying its title otherwise
+    fn displayed_details(&self, i: usize) -> Details {
+        match &self.details[i] {
+            Some(Ok(details)) => details.clone(),
+            _ => Details {
+                title: self.items[i].title.clone(),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Image of item `i`, or the placeholder until it has been downloaded
+    fn image(&self, i: Option<usize>) -> Handle {
+        i.and_then(|i| self.images[i].clone())
+            .unwrap_or_else(|| Handle::from_bytes(COVER_PLACEHOLDER_DATA))
+    }
+
+    /// Earlier parts of a series have to come before later ones
+    fn series_constraints(&self) -> Constraints {
+        let mut parts: Vec<(usize, &source::Series)> = self
+            .details
+            .iter()
+            .enumerate()
+            .filter_map(|(i, details)| match details {
+                Some(Ok(Details {
+                    series: Some(series),
+                    ..
+                })) if series.number.is_some() => Some((i, series)),
+                _ => None,
+            })
+            .collect();
+        parts.sort_by(|(_, a), (_, b)| {
+            a.id.cmp(&b.id)
+                .then(a.number.partial_cmp(&b.number).unwrap_or(Ordering::Equal))
+        });
+
+        // Parts sharing a number, like two editions of the same volume, form a group. Linking every part to
+        // every part of the next group is sufficient, as constraints are transitive.
+        let groups: Vec<_> = parts
+            .chunk_by(|(_, a), (_, b)| a.id == b.id && a.number == b.number)
+            .collect();
+        let mut constraints = Constraints::default();
+        for pair in groups.windows(2) {
+            let (earlier, later) = (pair[0], pair[1]);
+            if earlier[0].1.id != later[0].1.id {
+                continue;
+            }
+            for &(a, _) in earlier {
+                for &(b, _) in later {
+                    constraints.add(a, b);
+                }
+            }
+        }
+        constraints
+    }
+
+    fn series_label(&self, i: usize) -> Option<String> {
+        let Some(Ok(Details {
+            series: Some(series),
+            ..
+        })) = &self.details[i]
+        else {
+            return None;
+        };
+
+        Some(match series.number {
+            Some(number) => format!("{} #{number}", series.name),
+            None => series.name.clone(),
+        })
+    }
+
+    fn sort_value(&self, i: usize, key: SortKey) -> Option<sorting::Value> {
+        match (key, &self.details[i]) {
+            (SortKey::DateAdded, _) => self.items[i]
+                .date_added
+                .map(|date| sorting::Value::Number(date.num_days_from_ce().into())),
+            (_, Some(Ok(details))) => details.value(key),
+            // Items that haven't been fetched yet are at least known by title
+            (SortKey::Title, _) => Some(sorting::Value::Text(self.items[i].title.to_lowercase())),
+            _ => None,
+        }
+    }
+
+    /// Shows item `i`, or an empty placeholder
+    fn item_comparison(&self, i: Option<usize>) -> iced::Element<Message> {
+        let details = i.map(|i| self.displayed_details(i)).unwrap_or_default();
+        let description = if self.description_expanded
+            || details.description.chars().count() <= DESCRIPTION_PREVIEW_LENGTH
+        {
+            details.description
+        } else {
+            details
+                .description
+                .chars()
+                .take(DESCRIPTION_PREVIEW_LENGTH)
+                .collect::<String>()
+                + "…"
+        };
+
+        let comparison = iced::widget::row![
+            iced::widget::image(self.image(i)).height(iced::Fill),
+            iced::widget::column![
+                iced::widget::container(iced::widget::text(details.title)).padding(5),
+                iced::widget::container(iced::widget::text(details.subtitle)).padding(5),
+                iced::widget::container(iced::widget::text(details.facts.join(" · ")).size(12))
+                    .padding(5),
+                iced::widget::horizontal_rule(2),
+                iced::widget::scrollable(iced::widget::column![
+                    iced::widget::container(iced::widget::text(description)).padding(5),
+                    iced::widget::button(if self.description_expanded {
+                        "Show less"
+                    } else {
+                        "Show more"
+                    })
+                    .style(iced::widget::button::text)
+                    .on_press(Message::DescriptionToggled)
+                ])
+                .direction(scrollable::Direction::Vertical(scrollable::Scrollbar::new())) // .spacing(5)
+            ]
+        ];
+
+        comparison.into()
+    }
+}
+
+/// Fetches the given items along with their images, reporting each along with its index in the list
+pub fn fetch_items(
+    source: &'static dyn Source,
+    items: Vec<(usize, Item)>,
+    image_cache: std::path::PathBuf,
+) -> impl Stream<Item = Message> {
+    iced::stream::channel(1, move |mut output| async move {
+        // The scheduler takes care of not sending requests too fast
+        let concurrency = http::scheduler().config().max_concurrent_requests.max(1);
+        let image_cache = &image_cache;
+        let mut fetched = iced::futures::stream::iter(items)
+            .map(|(i, item)| async move {
+                (
+                    i,
+                    Box::new(source::fetch_item(source, &item, image_cache).await),
+                )
+            })
+            .buffer_unordered(concurrency);
+
+        while let Some(details) = fetched.next().await {
+            let _ = output.send(Message::DetailsFetched(details)).await;
+        }
+    })
+}
@@ -3,22 +3,28 @@ use strum::IntoEnumIterator;
 
 use crate::{
     backend,
-    common::{browser, helpers::Mode},
+    common::{browser, config::Config},
     scene::Error,
     scene::State,
+    scene::error::{self, Report},
+    scene::settings::Settings,
+    source::{self, SignInMethod, Source},
 };
 
 #[derive(Clone, Debug)]
 pub enum Message {
     LaunchAttempt,
-    LaunchSuccess(crate::common::helpers::Mode),
+    LaunchSuccess(&'static dyn Source),
+    CancelClick,
+    LaunchCancelled,
     ServerAddressInput(String),
     ServerPortInput(String),
     SettingsClick,
     BrowserSelected(browser::Browser),
     BrowserHeadlessToggle(bool),
-    ModeSelected(crate::common::helpers::Mode),
+    SourceSelected(&'static dyn Source),
     BackendConnected,
+    ErrorDismissed,
 }
 
 impl TryFrom<crate::scene::Message> for Message {
@@ -27,6 +33,7 @@ impl TryFrom<crate::scene::Message> for Message {
     fn try_from(message: crate::scene::Message) -> Result<Self, Self::Error> {
         match message {
             super::Message::Connected => Ok(Message::BackendConnected),
+            super::Message::Cancelled => Ok(Message::LaunchCancelled),
             super::Message::Launch(message) => Ok(message),
             _ => Err(Error::InvalidState {
                 state: "Launch".into(),
@@ -39,7 +46,7 @@ impl TryFrom<crate::scene::Message> for Message {
 impl From<crate::backend::uninitialized::Output> for Message {
     fn from(output: crate::backend::uninitialized::Output) -> Self {
         match output {
-            backend::uninitialized::Output::Initialized(mode) => Self::LaunchSuccess(mode),
+            backend::uninitialized::Output::Initialized(source) => Self::LaunchSuccess(source),
         }
     }
 }
@@ -52,35 +59,12 @@ impl From<Message> for crate::scene::Message {
 
 #[derive(Clone)]
 pub struct Launch {
+    config: Config,
     browser_driver_ip_input: String,
     browser_driver_port_input: String,
-    browser_headless: bool,
-    browser: browser::Browser,
-    mode: Mode,
-}
-
-impl Default for Launch {
-    fn default() -> Self {
-        let browser_driver_config = browser::DriverConfig {
-            driver_address: std::net::SocketAddrV4::new(
-                std::net::Ipv4Addr::new(127, 0, 0, 1),
-                4444,
-            ),
-            browser: browser::Browser::Firefox,
-            headless: true,
-        };
-
-        Self {
-            browser_driver_ip_input: browser_driver_config.driver_address.ip().to_string(),
-            browser_driver_port_input: browser_driver_config.driver_address.port().to_string(),
-            browser_headless: std::env::var("godric_headless")
-                .ok()
-                .and_then(|string| string.parse().ok())
-                .unwrap_or(true),
-            browser: browser::Browser::Firefox,
-            mode: Mode::Goodreads,
-        }
-    }
+    source: &'static dyn Source,
+    launching: bool,
+    error: Option<Report>,
 }
 
 impl From<Launch> for State {
@@ -90,48 +74,100 @@ impl From<Launch> for State {
 }
 
 impl Launch {
+    pub fn new(config: Config) -> Self {
+        Self {
+            browser_driver_ip_input: config.browser.driver_address.ip().to_string(),
+            browser_driver_port_input: config.browser.driver_address.port().to_string(),
+            config,
+            source: source::all()[0],
+            launching: false,
+            error: None,
+        }
+    }
+
+    /// Shows an error right away, e.g. one from loading the config
+    pub fn with_error(mut self, error: Report) -> Self {
+        self.error = Some(error);
+        self
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
     pub fn update(
         mut self,
         message: Result<Message, Error>,
     ) -> (State, Option<backend::Input>, Task<crate::scene::Message>) {
         let mut output = None;
         let mut state = None;
-        match message.expect("Failed to launch.") {
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                self.launching = false;
+                self.error = Some(Report::from(&error));
+                return (self.into(), output, Task::none());
+            }
+        };
+
+        match message {
             Message::LaunchAttempt => {
+                self.error = None;
                 if let Ok(ip) = self.browser_driver_ip_input.parse()
                     && let Ok(port) = self.browser_driver_port_input.parse()
                 {
+                    // Remember the driver for next time
+                    let driver_address = std::net::SocketAddrV4::new(ip, port);
+                    if self.config.browser.driver_address != driver_address {
+                        self.config.browser.driver_address = driver_address;
+                        self.save_config();
+                    }
+
                     output = Some(
                         backend::uninitialized::Input::Launch {
-                            browser_driver_config: browser::DriverConfig {
-                                browser: self.browser,
-                                driver_address: std::net::SocketAddrV4::new(ip, port),
-                                headless: self.browser_headless,
-                            },
-                            mode: self.mode,
+                            browser_driver_config: self.config.browser.clone(),
+                            config: Box::new(self.config.clone()),
+                            source: self.source,
                         }
                         .into(),
-                    )
+                    );
+                    self.launching = true;
                 }
             }
-            Message::LaunchSuccess(mode) => {
+            Message::CancelClick => output = Some(backend::Input::Cancel),
+            Message::LaunchCancelled => self.launching = false,
+            Message::LaunchSuccess(source) => {
                 state =
-                    State::Goodreads(crate::scene::goodreads::welcome::Welcome::default().into())
-                        .into()
+                    Some(crate::scene::welcome::Welcome::new(self.config.clone(), source).into())
             }
 
             Message::ServerAddressInput(address) => self.browser_driver_ip_input = address,
             Message::ServerPortInput(port) => self.browser_driver_port_input = port,
-            Message::SettingsClick => todo!(),
-            Message::BrowserSelected(browser) => self.browser = browser,
-            Message::BrowserHeadlessToggle(headless) => self.browser_headless = headless,
-            Message::ModeSelected(mode) => self.mode = mode,
-            Message::BackendConnected => println!("Backend connected!"),
+            Message::SettingsClick => {
+                state = Some(State::Settings(Settings::new(self.config.clone())))
+            }
+            Message::BrowserSelected(browser) => {
+                self.config.browser.browser = browser;
+                self.save_config();
+            }
+            Message::BrowserHeadlessToggle(headless) => {
+                self.config.browser.headless = headless;
+                self.save_config();
+            }
+            Message::SourceSelected(source) => self.source = source,
+            Message::BackendConnected => tracing::info!("Backend connected"),
+            Message::ErrorDismissed => self.error = None,
         };
 
         (state.unwrap_or(self.into()), output, Task::none())
     }
 
+    fn save_config(&mut self) {
+        if let Err(error) = self.config.save() {
+            self.error = Some(Report::new(error.as_ref()));
+        }
+    }
+
     pub fn view(&self) -> iced::Element<Message> {
         let browser_settings = {
             let server_ip_input = {
@@ -151,9 +187,14 @@ impl Launch {
                 iced::widget::column!(title, input)
             };
 
+            // Sources signing in in the browser window always get a visible one
+            let signs_in_in_browser = self.source.sign_in_method() == SignInMethod::Browser;
             let browser_headless_control = iced::widget::container(
-                iced::widget::checkbox("Headless browser", self.browser_headless)
-                    .on_toggle(Message::BrowserHeadlessToggle),
+                iced::widget::checkbox(
+                    "Headless browser",
+                    self.config.browser.headless && !signs_in_in_browser,
+                )
+                .on_toggle_maybe((!signs_in_in_browser).then_some(Message::BrowserHeadlessToggle)),
             );
 
             let browser_selection = {
@@ -161,7 +202,7 @@ impl Launch {
                     browser::Browser::iter()
                         .map(|browser| browser.to_string())
                         .collect::<Vec<_>>(),
-                    Some(self.browser.to_string()),
+                    Some(self.config.browser.browser.to_string()),
                     |selection| {
                         Message::BrowserSelected(
                             browser::Browser::try_from(selection.as_str())
@@ -184,31 +225,57 @@ impl Launch {
             .center_x(iced::Length::Fill)
             .center_y(iced::Length::Fill);
 
-        let mode_selection = iced::widget::pick_list(
-            crate::common::helpers::Mode::iter()
-                .map(|mode| mode.to_string())
+        let source_selection = iced::widget::pick_list(
+            source::all()
+                .iter()
+                .map(|source| source.name())
                 .collect::<Vec<_>>(),
-            Some(self.mode.to_string()),
+            Some(self.source.name()),
             |selection| {
-                Message::ModeSelected(
-                    crate::common::helpers::Mode::try_from(selection.as_str())
-                        .expect("Invalid mode selected!"),
-                )
+                Message::SourceSelected(source::find(selection).expect("Invalid source selected!"))
             },
         );
 
-        let launch_button = iced::widget::Button::new(
-            iced::widget::Container::new("Launch!").center_x(iced::Length::Fill),
+        let launch_button = if self.launching {
+            iced::widget::Button::new(
+                iced::widget::Container::new("Launching... Cancel").center_x(iced::Length::Fill),
+            )
+            .style(iced::widget::button::secondary)
+            .on_press(Message::CancelClick)
+            .width(iced::Length::Fill)
+        } else {
+            iced::widget::Button::new(
+                iced::widget::Container::new("Launch!").center_x(iced::Length::Fill),
+            )
+            .on_press(Message::LaunchAttempt)
+            .width(iced::Length::Fill)
+        };
+
+        let settings_button = iced::widget::Button::new(
+            iced::widget::Container::new("Settings").center_x(iced::Length::Fill),
         )
-        .on_press(Message::LaunchAttempt)
+        .style(iced::widget::button::secondary)
+        .on_press(Message::SettingsClick)
         .width(iced::Length::Fill);
 
-        let launch_prompt = iced::widget::column!(mode_selection, launch_button)
+        let launch_prompt = iced::widget::column!(source_selection, launch_button, settings_button)
             .spacing(10)
             .padding(10)
             .align_x(iced::Alignment::Center);
 
-        let content = iced::widget::column!(browser_settings, image, launch_prompt)
+        let error = self.error.as_ref().map(|report| {
+            error::banner(
+                report,
+                Message::ErrorDismissed,
+                Some(Message::LaunchAttempt),
+            )
+        });
+
+        let content = iced::widget::column![]
+            .push_maybe(error)
+            .push(browser_settings)
+            .push(image)
+            .push(launch_prompt)
             .width(iced::Length::Fill)
             .height(iced::Length::Fill);
 
@@ -0,0 +1,496 @@
+use iced::Task;
+use strum::IntoEnumIterator;
+
+use crate::{
+    backend,
+    common::{
+        browser,
+        config::Config,
+        http,
+        keybindings::{self, Action},
+        logging, selectors,
+        sorting::{Direction, SortKey},
+    },
+    scene::Error,
+    scene::State,
+    scene::error::{self, Report},
+    scene::launch::Launch,
+};
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    ServerAddressInput(String),
+    ServerPortInput(String),
+    BrowserSelected(browser::Browser),
+    BrowserHeadlessToggle(bool),
+    ElementTimeoutInput(String),
+    PollIntervalInput(String),
+    ThemeSelected(String),
+    LogLevelSelected(String),
+    MaxConcurrentRequestsInput(String),
+    RequestsPerSecondInput(String),
+    JitterInput(String),
+    UserAgentInput(String),
+    CacheDirInput(String),
+    DefaultShelfInput(String),
+    SteamCountryInput(String),
+    SortKeySelected(SortKey),
+    SortDirectionSelected(Direction),
+    RebindStarted(Action),
+    KeyPressed(iced::keyboard::Key),
+    KeyBindingsReset,
+    SelectorOverrideClick,
+    BackClick,
+    ErrorDismissed,
+}
+
+impl TryFrom<crate::scene::Message> for Message {
+    type Error = crate::backend::Error;
+
+    fn try_from(message: crate::scene::Message) -> Result<Self, Self::Error> {
+        match message {
+            super::Message::Settings(message) => Ok(message),
+            _ => Err(Error::InvalidState {
+                state: "Settings".into(),
+                message: format!("{:?}", message),
+            }),
+        }
+    }
+}
+
+impl From<Message> for crate::scene::Message {
+    fn from(message: Message) -> Self {
+        Self::Settings(message)
+    }
+}
+
+/// Text inputs are kept as typed, and only make it into the config once they parse
+#[derive(Clone)]
+pub struct Settings {
+    config: Config,
+    browser_driver_ip_input: String,
+    browser_driver_port_input: String,
+    element_timeout_input: String,
+    poll_interval_input: String,
+    max_concurrent_requests_input: String,
+    requests_per_second_input: String,
+    jitter_input: String,
+    cache_dir_input: String,
+    /// Action waiting for a key press to be bound to
+    rebinding: Option<Action>,
+    /// Whether there is a selector file overriding the built-in one
+    selector_override: bool,
+    error: Option<Report>,
+}
+
+impl From<Settings> for State {
+    fn from(state: Settings) -> Self {
+        State::Settings(state)
+    }
+}
+
+impl Settings {
+    pub fn new(config: Config) -> Self {
+        Self {
+            browser_driver_ip_input: config.browser.driver_address.ip().to_string(),
+            browser_driver_port_input: config.browser.driver_address.port().to_string(),
+            element_timeout_input: config.browser.waits.timeout.as_millis().to_string(),
+            poll_interval_input: config.browser.waits.interval.as_millis().to_string(),
+            max_concurrent_requests_input: config.requests.max_concurrent_requests.to_string(),
+            requests_per_second_input: config.requests.requests_per_second.to_string(),
+            jitter_input: config.requests.jitter.as_millis().to_string(),
+            cache_dir_input: config.cache_dir.display().to_string(),
+            config,
+            rebinding: None,
+            selector_override: selectors::override_path().is_some_and(|path| path.exists()),
+            error: None,
+        }
+    }
+
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    pub fn update(
+        mut self,
+        message: Result<Message, Error>,
+    ) -> (State, Option<backend::Input>, Task<crate::scene::Message>) {
+        let message = match message {
+            Ok(message) => message,
+            Err(error) => {
+                self.error = Some(Report::from(&error));
+                return (self.into(), None, Task::none());
+            }
+        };
+
+        let previous = self.config.clone();
+        match message {
+            Message::ServerAddressInput(address) => {
+                if let Ok(ip) = address.parse() {
+                    self.config.browser.driver_address.set_ip(ip);
+                }
+                self.browser_driver_ip_input = address;
+            }
+            Message::ServerPortInput(port) => {
+                if let Ok(port) = port.parse() {
+                    self.config.browser.driver_address.set_port(port);
+                }
+                self.browser_driver_port_input = port;
+            }
+            Message::BrowserSelected(browser) => self.config.browser.browser = browser,
+            Message::BrowserHeadlessToggle(headless) => self.config.browser.headless = headless,
+            Message::ElementTimeoutInput(timeout) => {
+                if let Ok(timeout) = timeout.parse() {
+                    self.config.browser.waits.timeout = std::time::Duration::from_millis(timeout);
+                }
+                self.element_timeout_input = timeout;
+            }
+            Message::PollIntervalInput(interval) => {
+                if let Ok(interval) = interval.parse::<u64>()
+                    && interval > 0
+                {
+                    self.config.browser.waits.interval = std::time::Duration::from_millis(interval);
+                }
+                self.poll_interval_input = interval;
+            }
+            Message::ThemeSelected(theme) => self.config.theme = theme,
+            Message::LogLevelSelected(level) => self.config.log_level = level,
+            Message::MaxConcurrentRequestsInput(count) => {
+                if let Ok(count) = count.parse::<usize>()
+                    && count > 0
+                {
+                    self.config.requests.max_concurrent_requests = count;
+                }
+                self.max_concurrent_requests_input = count;
+            }
+            Message::RequestsPerSecondInput(rate) => {
+                if let Ok(rate) = rate.parse::<f64>()
+                    && rate > 0.0
+                {
+                    self.config.requests.requests_per_second = rate;
+                }
+                self.requests_per_second_input = rate;
+            }
+            Message::JitterInput(jitter) => {
+                if let Ok(jitter) = jitter.parse() {
+                    self.config.requests.jitter = std::time::Duration::from_millis(jitter);
+                }
+                self.jitter_input = jitter;
+            }
+            Message::UserAgentInput(user_agent) => self.config.requests.user_agent = user_agent,
+            Message::CacheDirInput(directory) => {
+                if !directory.trim().is_empty() {
+                    self.config.cache_dir = directory.trim().into();
+                }
+                self.cache_dir_input = directory;
+            }
+            Message::DefaultShelfInput(shelf) => self.config.default_shelf = shelf,
+            Message::SteamCountryInput(country) => {
+                self.config.steam_country = country.trim().to_uppercase()
+            }
+            Message::SortKeySelected(key) => self.config.default_sorting.key = key,
+            Message::SortDirectionSelected(direction) => {
+                self.config.default_sorting.direction = direction
+            }
+            Message::RebindStarted(action) => self.rebinding = Some(action),
+            Message::KeyPressed(key) => {
+                if let Some(action) = self.rebinding.take()
+                    && !keybindings::is_escape(&key)
+                {
+                    self.config.key_bindings.rebind(action, &key);
+                }
+            }
+            Message::KeyBindingsReset => self.config.key_bindings = Default::default(),
+            Message::SelectorOverrideClick => match selectors::create_override() {
+                Ok(_) => self.selector_override = true,
+                Err(error) => self.error = Some(Report::new(error.as_ref())),
+            },
+            Message::BackClick => {
+                return (Launch::new(self.config).into(), None, Task::none());
+            }
+            Message::ErrorDismissed => self.error = None,
+        }
+
+        if self.config != previous {
+            if self.config.requests != previous.requests {
+                http::configure(self.config.requests.clone());
+            }
+            if let Err(error) = self.config.save() {
+                self.error = Some(Report::new(error.as_ref()));
+            }
+        }
+
+        (self.into(), None, Task::none())
+    }
+
+    pub fn subscription(&self) -> iced::Subscription<Message> {
+        if self.rebinding.is_some() {
+            iced::keyboard::on_key_press(|key, _modifiers| Some(Message::KeyPressed(key)))
+        } else {
+            iced::Subscription::none()
+        }
+    }
+
+    pub fn view(&self) -> iced::Element<Message> {
+        let browser_settings = iced::widget::column![
+            iced::widget::text("Browser").size(20),
+            labelled(
+                "Driver address",
+                iced::widget::TextInput::new("127.0.0.1", &self.browser_driver_ip_input)
+                    .on_input(Message::ServerAddressInput)
+                    .into()
+            ),
+            labelled(
+                "Driver port",
+                iced::widget::TextInput::new("4444", &self.browser_driver_port_input)
+                    .on_input(Message::ServerPortInput)
+                    .into()
+            ),
+            labelled(
+                "Browser",
+                iced::widget::pick_list(
+                    browser::Browser::iter()
+                        .map(|browser| browser.to_string())
+                        .collect::<Vec<_>>(),
+                    Some(self.config.browser.browser.to_string()),
+                    |selection| {
+                        Message::BrowserSelected(
+                            browser::Browser::try_from(selection.as_str())
+                                .expect("Invalid browser selected!"),
+                        )
+                    },
+                )
+                .into()
+            ),
+            iced::widget::checkbox("Headless browser", self.config.browser.headless)
+                .on_toggle(Message::BrowserHeadlessToggle),
+            labelled(
+                "Element timeout (ms)",
+                iced::widget::TextInput::new("10000", &self.element_timeout_input)
+                    .on_input(Message::ElementTimeoutInput)
+                    .into()
+            ),
+            labelled(
+                "Poll interval (ms)",
+                iced::widget::TextInput::new("250", &self.poll_interval_input)
+                    .on_input(Message::PollIntervalInput)
+                    .into()
+            ),
+        ]
+        .spacing(5);
+
+        let appearance = iced::widget::column![
+            iced::widget::text("Appearance").size(20),
+            labelled(
+                "Theme",
+                iced::widget::pick_list(
+                    iced::Theme::ALL
+                        .iter()
+                        .map(|theme| theme.to_string())
+                        .collect::<Vec<_>>(),
+                    Some(self.config.theme.clone()),
+                    Message::ThemeSelected,
+                )
+                .into()
+            ),
+        ]
+        .spacing(5);
+
+        let diagnostics = iced::widget::column![
+            iced::widget::text("Diagnostics").size(20),
+            labelled(
+                "Log level (on restart)",
+                iced::widget::pick_list(
+                    logging::LEVELS.map(String::from).to_vec(),
+                    Some(self.config.log_level.clone()),
+                    Message::LogLevelSelected,
+                )
+                .into()
+            ),
+            iced::widget::text(format!(
+                "Logs are saved to {}",
+                logging::directory().display()
+            ))
+            .size(12),
+            self.selector_controls(),
+        ]
+        .spacing(5);
+
+        let requests = iced::widget::column![
+            iced::widget::text("Requests").size(20),
+            labelled(
+                "Concurrent requests",
+                iced::widget::TextInput::new("4", &self.max_concurrent_requests_input)
+                    .on_input(Message::MaxConcurrentRequestsInput)
+                    .into()
+            ),
+            labelled(
+                "Requests per second",
+                iced::widget::TextInput::new("10", &self.requests_per_second_input)
+                    .on_input(Message::RequestsPerSecondInput)
+                    .into()
+            ),
+            labelled(
+                "Jitter (ms)",
+                iced::widget::TextInput::new("30", &self.jitter_input)
+                    .on_input(Message::JitterInput)
+                    .into()
+            ),
+            labelled(
+                "User agent",
+                iced::widget::TextInput::new("Mozilla/5.0", &self.config.requests.user_agent)
+                    .on_input(Message::UserAgentInput)
+                    .into()
+            ),
+            labelled(
+                "Cover cache",
+                iced::widget::TextInput::new("", &self.cache_dir_input)
+                    .on_input(Message::CacheDirInput)
+                    .into()
+            ),
+        ]
+        .spacing(5);
+
+        let sorting = iced::widget::column![
+            iced::widget::text("Sorting").size(20),
+            labelled(
+                "Default shelf",
+                iced::widget::TextInput::new("to-read", &self.config.default_shelf)
+                    .on_input(Message::DefaultShelfInput)
+                    .into()
+            ),
+            labelled(
+                "Steam store country",
+                iced::widget::TextInput::new("US", &self.config.steam_country)
+                    .on_input(Message::SteamCountryInput)
+                    .into()
+            ),
+            labelled(
+                "Default sorting",
+                iced::widget::row![
+                    iced::widget::pick_list(
+                        SortKey::iter()
+                            .map(|key| key.to_string())
+                            .collect::<Vec<_>>(),
+                        Some(self.config.default_sorting.key.to_string()),
+                        |selection| {
+                            Message::SortKeySelected(
+                                SortKey::try_from(selection.as_str())
+                                    .expect("Invalid sort key selected!"),
+                            )
+                        },
+                    ),
+                    iced::widget::pick_list(
+                        Direction::iter()
+                            .map(|direction| direction.to_string())
+                            .collect::<Vec<_>>(),
+                        Some(self.config.default_sorting.direction.to_string()),
+                        |selection| {
+                            Message::SortDirectionSelected(
+                                Direction::try_from(selection.as_str())
+                                    .expect("Invalid sort direction selected!"),
+                            )
+                        },
+                    ),
+                ]
+                .spacing(5)
+                .into()
+            ),
+        ]
+        .spacing(5);
+
+        let bindings = Action::iter().map(|action| {
+            let status = if self.rebinding == Some(action) {
+                "Press a key (Esc to cancel)".to_string()
+            } else {
+                self.config.key_bindings.keys(action).join("/")
+            };
+            iced::widget::row![
+                iced::widget::text(action.to_string()).width(200),
+                iced::widget::text(status).width(200),
+                iced::widget::button("Rebind").on_press(Message::RebindStarted(action)),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        });
+        let key_bindings = iced::widget::column![iced::widget::text("Key bindings").size(20)]
+            .extend(bindings)
+            .push(
+                iced::widget::button("Reset to defaults")
+                    .style(iced::widget::button::secondary)
+                    .on_press(Message::KeyBindingsReset),
+            )
+            .spacing(5);
+
+        let error = self
+            .error
+            .as_ref()
+            .map(|report| error::banner(report, Message::ErrorDismissed, None));
+
+        let location = crate::common::config::Config::path()
+            .map(|path| format!("Saved to {}", path.display()))
+            .unwrap_or_default();
+
+        let content = iced::widget::column![]
+            .push_maybe(error)
+            .push(
+                iced::widget::row![
+                    iced::widget::button("Back").on_press(Message::BackClick),
+                    iced::widget::text(location).size(12),
+                ]
+                .spacing(10)
+                .align_y(iced::Alignment::Center),
+            )
+            .extend([
+                browser_settings.into(),
+                appearance.into(),
+                requests.into(),
+                sorting.into(),
+                key_bindings.into(),
+                diagnostics.into(),
+            ])
+            .spacing(20)
+            .padding(10);
+
+        iced::widget::scrollable(content).into()
+    }
+}
+
+impl Settings {
+    /// Where the selector overrides are, or a way to create them from the built-in selectors
+    fn selector_controls(&self) -> iced::Element<Message> {
+        let Some(path) = selectors::override_path() else {
+            return iced::widget::Space::new(0, 0).into();
+        };
+
+        if self.selector_override {
+            iced::widget::text(format!(
+                "Selectors are read from {}, changes apply right away",
+                path.display()
+            ))
+            .size(12)
+            .into()
+        } else {
+            iced::widget::row![
+                iced::widget::text("Using built-in selectors").size(12),
+                iced::widget::button("Create editable copy")
+                    .style(iced::widget::button::secondary)
+                    .on_press(Message::SelectorOverrideClick),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+            .into()
+        }
+    }
+}
+
+/// Setting with its name in front
+fn labelled<'a>(
+    label: &'a str,
+    input: iced::Element<'a, Message>,
+) -> iced::widget::Row<'a, Message> {
+    iced::widget::row![iced::widget::text(label).width(200), input]
+        .spacing(10)
+        .align_y(iced::Alignment::Center)
+}
@@ -0,0 +1,499 @@
+use crate::{
+    backend,
+    common::{
+        config::Config,
+        credential_store::CredentialStore,
+        helpers::{Credentials, Secret},
+    },
+    scene::{
+        self, State,
+        error::{self, Report},
+    },
+    source::{Challenge, SignInMethod, Source},
+};
+
+use color_eyre::Result;
+use iced::Task;
+
+#[derive(Clone, Debug)]
+pub struct Welcome {
+    config: Config,
+    source: &'static dyn Source,
+    credentials: Credentials,
+    /// Where credentials are remembered, if the platform has a place for it
+    store: Option<CredentialStore>,
+    remember: bool,
+    passphrase: Secret,
+    /// Whether the remembered credentials were unlocked, so saving over them loses nothing
+    unlocked: bool,
+    /// Whether the user agreed to replace remembered credentials they didn't unlock
+    replace_confirmed: bool,
+    /// Credentials to remember once signing in with them worked
+    pending_save: Option<(Credentials, Secret)>,
+    /// Items of a successful sign in, held back until the credentials are saved
+    signed_in_items: Option<Vec<crate::source::Item>>,
+    signing_in: bool,
+    /// Progress of fetching the list, as reported by the source
+    fetch_progress: Option<(usize, usize)>,
+    /// Challenge the source showed while signing in, waiting to be answered
+    challenge: Option<Challenge>,
+    challenge_answer: Secret,
+    captcha: Option<iced::widget::image::Handle>,
+    error: Option<Report>,
+}
+
+impl Welcome {
+    pub fn new(config: Config, source: &'static dyn Source) -> Self {
+        let store = CredentialStore::default_location();
+        Self {
+            config,
+            source,
+            // Only a fallback for when nothing is remembered, or the store is still locked
+            credentials: Credentials {
+                email: std::env::var("godric_email").unwrap_or("".to_string()),
+                password: std::env::var("godric_password")
+                    .unwrap_or("".to_string())
+                    .into(),
+            },
+            remember: false,
+            store,
+            passphrase: Default::default(),
+            unlocked: false,
+            replace_confirmed: false,
+            pending_save: None,
+            signed_in_items: None,
+            signing_in: false,
+            fetch_progress: None,
+            challenge: None,
+            challenge_answer: Default::default(),
+            captcha: None,
+            error: None,
+        }
+    }
+}
+
+impl From<Welcome> for State {
+    fn from(state: Welcome) -> Self {
+        Self::Welcome(state)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum Message {
+    EmailInput(String),
+    PasswordInput(Secret),
+    RememberToggled(bool),
+    ReplaceToggled(bool),
+    PassphraseInput(Secret),
+    UnlockAttempt,
+    CredentialsUnlocked(Result<Credentials, Report>),
+    CredentialsSaved(Result<(), Report>),
+    ForgetClick,
+    LoginAttempt,
+    CancelClick,
+    LoginCancelled,
+    ChallengeRequired(Challenge),
+    ChallengeAnswerInput(Secret),
+    ChallengeAnswerSubmit,
+    ItemsFetched { fetched: usize, total: usize },
+    LoginSuccess { items: Vec<crate::source::Item> },
+    ErrorDismissed,
+}
+
+impl From<backend::welcome::Output> for Message {
+    fn from(output: backend::welcome::Output) -> Self {
+        match output {
+            backend::welcome::Output::ChallengeRequired(challenge) => {
+                Self::ChallengeRequired(challenge)
+            }
+            backend::welcome::Output::ItemsFetched { fetched, total } => {
+                Self::ItemsFetched { fetched, total }
+            }
+            backend::welcome::Output::LoginSuccess { items } => Self::LoginSuccess { items },
+        }
+    }
+}
+
+impl From<Message> for scene::Message {
+    fn from(message: Message) -> Self {
+        Self::Welcome(message)
+    }
+}
+
+impl TryFrom<scene::Message> for Message {
+    type Error = crate::backend::Error;
+
+    fn try_from(message: scene::Message) -> Result<Self, Self::Error> {
+        match message {
+            scene::Message::Welcome(message) => Ok(message),
+            // Only signing in runs as a backend job here
+            scene::Message::Cancelled => Ok(Self::LoginCancelled),
+            _ => Err(Self::Error::InvalidState {
+                state: "Welcome".into(),
+                message: format!("{:?}", message),
+            }),
+        }
+    }
+}
+
+impl Welcome {
+    pub fn update(
+        mut self,
+        message: Result<Message, crate::backend::Error>,
+    ) -> (State, Option<crate::backend::Input>, Task<scene::Message>) {
+        let mut output: Option<crate::backend::Input> = None;
+        let mut state = None;
+        let mut task = Task::none();
+
+        match message {
+            Ok(message) => match message {
+                Message::EmailInput(email) => self.credentials.email = email,
+                Message::PasswordInput(password) => self.credentials.password = password,
+                Message::RememberToggled(remember) => self.remember = remember,
+                Message::ReplaceToggled(confirmed) => self.replace_confirmed = confirmed,
+                Message::PassphraseInput(passphrase) => self.passphrase = passphrase,
+                Message::UnlockAttempt => {
+                    if let Some(store) = self.store.clone() {
+                        let passphrase = self.passphrase.clone();
+                        task = Task::perform(
+                            off_thread(move || store.load(passphrase.expose())),
+                            Message::CredentialsUnlocked,
+                        )
+                        .map(scene::Message::Welcome);
+                    }
+                }
+                Message::CredentialsUnlocked(result) => match result {
+                    Ok(credentials) => {
+                        self.credentials = credentials;
+                        self.unlocked = true;
+                        self.remember = true;
+                    }
+                    Err(report) => self.error = Some(report),
+                },
+                Message::CredentialsSaved(result) => {
+                    let error = result.err();
+                    match self.signed_in_items.take() {
+                        Some(items) => {
+                            if let Some(report) = &error {
+                                tracing::error!("Failed to remember credentials: {report}");
+                            }
+                            let (home, home_task) = self.enter_home(items);
+                            task = home_task;
+                            state = Some(State::Home(home.with_error(error)));
+                        }
+                        None => self.error = error,
+                    }
+                }
+                Message::ForgetClick => {
+                    if let Some(store) = &self.store
+                        && let Err(error) = store.forget()
+                    {
+                        self.error = Some(Report::new(error.as_ref()));
+                    }
+                    self.remember = false;
+                    self.unlocked = false;
+                    self.replace_confirmed = false;
+                    self.passphrase = Default::default();
+                }
+                Message::LoginAttempt => {
+                    if self.remember
+                        && self.source.sign_in_method() == SignInMethod::Credentials
+                        && let Some(store) = self.store.clone()
+                    {
+                        if self.passphrase.is_empty() {
+                            self.error = Some(Report {
+                                summary: "Enter a passphrase to remember your credentials with"
+                                    .to_string(),
+                                causes: vec![],
+                            });
+                            return (self.into(), None, Task::none());
+                        }
+                        if store.exists() && !self.unlocked && !self.replace_confirmed {
+                            self.error = Some(Report {
+                                summary:
+                                    "Unlock the remembered credentials, or confirm replacing them"
+                                        .to_string(),
+                                causes: vec![],
+                            });
+                            return (self.into(), None, Task::none());
+                        }
+
+                        // Only remembered once signing in proves them right
+                        self.pending_save =
+                            Some((self.credentials.clone(), self.passphrase.clone()));
+                    } else {
+                        self.pending_save = None;
+                    }
+
+                    self.error = None;
+                    self.signing_in = true;
+                    self.fetch_progress = None;
+                    self.challenge = None;
+                    output = Some(
+                        backend::welcome::Input::LoginAttempt {
+                            credentials: self.credentials.clone(),
+                        }
+                        .into(),
+                    )
+                }
+                Message::CancelClick => output = Some(crate::backend::Input::Cancel),
+                Message::LoginCancelled => {
+                    self.signing_in = false;
+                    self.pending_save = None;
+                    self.challenge = None;
+                }
+                Message::ChallengeRequired(challenge) => {
+                    // Challenges in the browser are solved while the backend waits
+                    self.signing_in = matches!(challenge, Challenge::InBrowser);
+                    self.captcha = match &challenge {
+                        Challenge::Captcha { image } => {
+                            Some(iced::widget::image::Handle::from_bytes(image.clone()))
+                        }
+                        _ => None,
+                    };
+                    self.challenge_answer = Default::default();
+                    self.challenge = Some(challenge);
+                }
+                Message::ChallengeAnswerInput(answer) => self.challenge_answer = answer,
+                Message::ChallengeAnswerSubmit => {
+                    self.error = None;
+                    self.signing_in = true;
+                    self.challenge = None;
+                    output = Some(
+                        backend::welcome::Input::ChallengeAnswer {
+                            credentials: self.credentials.clone(),
+                            answer: std::mem::take(&mut self.challenge_answer),
+                        }
+                        .into(),
+                    )
+                }
+                Message::ItemsFetched { fetched, total } => {
+                    self.fetch_progress = Some((fetched, total))
+                }
+                Message::LoginSuccess { items } => {
+                    match (self.pending_save.take(), self.store.clone()) {
+                        (Some((credentials, passphrase)), Some(store)) => {
+                            self.signed_in_items = Some(items);
+                            task = Task::perform(
+                                off_thread(move || store.save(&credentials, passphrase.expose())),
+                                Message::CredentialsSaved,
+                            )
+                            .map(scene::Message::Welcome);
+                        }
+                        _ => {
+                            let (home, home_task) = self.enter_home(items);
+                            task = home_task;
+                            state = Some(State::Home(home));
+                        }
+                    }
+                }
+                Message::ErrorDismissed => self.error = None,
+            },
+            Err(error) => {
+                self.signing_in = false;
+                self.challenge = None;
+                self.pending_save = None;
+                let report = Report::from(&error);
+                tracing::error!("Sign in failed: {report}");
+                self.error = Some(report);
+            }
+        }
+
+        (state.unwrap_or(self.into()), output, task)
+    }
+
+    pub fn view(&self) -> iced::Element<Message> {
+        let image = iced::widget::container(iced::widget::image("Assets/Logo/Welcome.png"))
+            .center_x(iced::Length::Fill)
+            .center_y(iced::Length::Fill);
+
+        let email_input = {
+            let title = iced::widget::text("E-Mail");
+            let input = iced::widget::TextInput::new("Godric@example.com", &self.credentials.email)
+                .on_input(Message::EmailInput)
+                .padding(10);
+            iced::widget::column!(title, input)
+        };
+
+        let password_input = {
+            let title = iced::widget::text("Password");
+            let input =
+                iced::widget::TextInput::new("Swordfish", self.credentials.password.expose())
+                    .on_input(|password| Message::PasswordInput(password.into()))
+                    .secure(true)
+                    .padding(10);
+            iced::widget::column!(title, input)
+        };
+        let login_details = iced::widget::row!(email_input, password_input).spacing(10);
+        let uses_credentials = self.source.sign_in_method() == SignInMethod::Credentials;
+
+        let remember_controls = self.store.as_ref().map(|store| {
+            let passphrase_input =
+                iced::widget::TextInput::new("Master passphrase", self.passphrase.expose())
+                    .on_input(|passphrase| Message::PassphraseInput(passphrase.into()))
+                    .on_submit(Message::UnlockAttempt)
+                    .secure(true)
+                    .padding(10);
+
+            let mut controls = iced::widget::row![
+                iced::widget::checkbox("Remember me", self.remember)
+                    .on_toggle(Message::RememberToggled),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center);
+            if self.remember || store.exists() {
+                controls = controls.push(passphrase_input);
+            }
+            if self.remember && store.exists() && !self.unlocked {
+                controls = controls.push(
+                    iced::widget::checkbox(
+                        "Replace remembered credentials",
+                        self.replace_confirmed,
+                    )
+                    .on_toggle(Message::ReplaceToggled),
+                );
+            }
+            if store.exists() {
+                controls = controls
+                    .push(iced::widget::button("Unlock").on_press(Message::UnlockAttempt))
+                    .push(
+                        iced::widget::button("Forget")
+                            .style(iced::widget::button::secondary)
+                            .on_press(Message::ForgetClick),
+                    );
+            }
+            controls
+        });
+
+        let login_button = if self.signing_in {
+            iced::widget::Button::new(
+                iced::widget::Container::new("Signing in... Cancel").center_x(iced::Length::Fill),
+            )
+            .style(iced::widget::button::secondary)
+            .on_press(Message::CancelClick)
+        } else {
+            let label = if uses_credentials {
+                "Sign in!".to_string()
+            } else {
+                format!("Sign in with {}", self.source.name())
+            };
+            iced::widget::Button::new(
+                iced::widget::Container::new(iced::widget::text(label))
+                    .center_x(iced::Length::Fill),
+            )
+            .on_press(Message::LoginAttempt)
+        };
+        let login_button = iced::widget::Container::new(login_button.width(iced::Length::Fill))
+            .center_x(iced::Length::Fill);
+
+        let progress = self.signing_in.then(|| {
+            let (fetched, total) = self.fetch_progress.unwrap_or((0, 1));
+            let status = match self.fetch_progress {
+                Some((fetched, total)) => {
+                    format!("Fetching {} ({fetched}/{total})", self.source.terms().items)
+                }
+                None => "Signing in...".to_string(),
+            };
+            iced::widget::row![
+                iced::widget::progress_bar(0.0..=total as f32, fetched as f32).height(10),
+                iced::widget::text(status),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+        });
+
+        let login_prompt = iced::widget::column![]
+            .push_maybe(uses_credentials.then_some(login_details))
+            .push_maybe(remember_controls.filter(|_| uses_credentials))
+            .push(login_button)
+            .push_maybe(progress)
+            .push_maybe(self.challenge_prompt())
+            .spacing(10)
+            .padding(10);
+
+        let error = self.error.as_ref().map(|report| {
+            error::banner(report, Message::ErrorDismissed, Some(Message::LoginAttempt))
+        });
+
+        let content = iced::widget::column![]
+            .push_maybe(error)
+            .push(image)
+            .push(login_prompt)
+            .width(iced::Length::Fill)
+            .height(iced::Length::Fill);
+
+        iced::widget::Container::new(content)
+            .center_x(iced::Length::Fill)
+            .center_y(iced::Length::Fill)
+            .into()
+    }
+}
+
+impl Welcome {
+    /// Opens the fetched list and starts fetching the details of its items
+    fn enter_home(
+        &self,
+        items: Vec<crate::source::Item>,
+    ) -> (super::home::Home, Task<scene::Message>) {
+        let mut home = super::home::Home::new(items, self.source, &self.config);
+        let task = home.fetch_all().map(|message| message.into());
+        (home, task)
+    }
+
+    /// Asks for whatever the source wants before letting the user sign in
+    fn challenge_prompt(&self) -> Option<iced::Element<Message>> {
+        let answer_input = |placeholder| {
+            iced::widget::row![
+                iced::widget::TextInput::new(placeholder, self.challenge_answer.expose())
+                    .on_input(|answer| Message::ChallengeAnswerInput(answer.into()))
+                    .on_submit(Message::ChallengeAnswerSubmit)
+                    .padding(10),
+                iced::widget::button("Continue").on_press(Message::ChallengeAnswerSubmit),
+            ]
+            .spacing(10)
+            .align_y(iced::Alignment::Center)
+        };
+
+        let prompt: iced::Element<Message> = match self.challenge.as_ref()? {
+            Challenge::Otp => iced::widget::column![
+                iced::widget::text("Enter the one-time password you were sent"),
+                answer_input("123456"),
+            ]
+            .spacing(5)
+            .into(),
+            Challenge::Captcha { .. } => iced::widget::column![
+                iced::widget::text("Enter the characters shown to continue signing in"),
+            ]
+            .push_maybe(
+                self.captcha
+                    .as_ref()
+                    .map(|captcha| iced::widget::image(captcha.clone())),
+            )
+            .push(answer_input("Characters"))
+            .spacing(5)
+            .into(),
+            Challenge::InBrowser => match self.source.sign_in_method() {
+                SignInMethod::Credentials => iced::widget::text(
+                    "Solve the challenge in the browser window. Signing in carries on once it's solved.",
+                ),
+                SignInMethod::Browser => iced::widget::text(format!(
+                    "Sign in to {} in the browser window. Godric carries on once you're signed in.",
+                    self.source.name()
+                )),
+            }
+            .into(),
+        };
+
+        Some(prompt)
+    }
+}
+
+/// Runs slow work, like deriving keys from a passphrase, without blocking the UI
+async fn off_thread<T: Send + 'static>(
+    work: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T, Report> {
+    match tokio::task::spawn_blocking(work).await {
+        Ok(result) => result.map_err(|error| Report::new(error.as_ref())),
+        Err(error) => Err(Report::new(&error)),
+    }
+}
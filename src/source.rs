@@ -0,0 +1,349 @@
+pub mod goodreads;
+pub mod steam;
+
+use color_eyre::eyre::eyre;
+use futures::future::BoxFuture;
+
+use crate::{
+    backend,
+    common::{
+        browser,
+        config::Config,
+        helpers::{Credentials, Secret},
+        http,
+        sorting::{SortKey, Value},
+        webdriver::{self, Session},
+    },
+};
+
+/// Every source lists can be sorted from, in the order they are offered
+static SOURCES: [&dyn Source; 2] = [&goodreads::Goodreads, &steam::Steam];
+
+/// Attempts at fetching details, before giving up on transient errors
+const FETCH_ATTEMPTS: u32 = 4;
+
+/// Delay before the first retry, doubling with every further attempt
+const RETRY_DELAY: std::time::Duration = std::time::Duration::from_millis(500);
+
+pub fn all() -> &'static [&'static dyn Source] {
+    &SOURCES
+}
+
+pub fn find(name: &str) -> Option<&'static dyn Source> {
+    SOURCES.iter().copied().find(|source| source.name() == name)
+}
+
+/// Somewhere lists to sort come from, like a Goodreads shelf or a Steam wishlist. Each source is a module of its
+/// own, and is offered once it's added to `SOURCES`.
+pub trait Source: Send + Sync {
+    /// Shown when picking a source, and in logs
+    fn name(&self) -> &'static str;
+
+    fn terms(&self) -> &'static Terms;
+
+    fn sign_in_method(&self) -> SignInMethod;
+
+    /// Keys that items of this source have values for
+    fn sort_keys(&self) -> &'static [SortKey];
+
+    /// Signs in through the browser. `answer` answers the challenge handed back by the previous attempt, if any.
+    fn login<'a>(
+        &'a self,
+        session: &'a Session,
+        credentials: &'a Credentials,
+        answer: Option<&'a Secret>,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<SignIn, Error>>;
+
+    /// Fetches the list of the signed in user, in its current order. Progress is reported as
+    /// `backend::welcome::Output::ItemsFetched`.
+    fn fetch_items<'a>(
+        &'a self,
+        user_id: &'a str,
+        config: &'a Config,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<Vec<Item>, Error>>;
+
+    /// Fetches what is shown of an item while sorting
+    fn fetch_details<'a>(&'a self, item: &'a Item) -> BoxFuture<'a, Result<Details, FetchError>>;
+
+    /// Whether `apply_order` writes anything back
+    fn can_apply_order(&self) -> bool {
+        false
+    }
+
+    /// Writes a new order back to the list, with the first item first
+    fn apply_order<'a>(
+        &'a self,
+        _session: &'a Session,
+        _user_id: &'a str,
+        _items: &'a [Item],
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async {
+            Err(eyre!("Lists from {} can't be reordered from Godric", self.name()).into())
+        })
+    }
+}
+
+impl std::fmt::Debug for dyn Source {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// How a source talks about its items in the UI
+#[derive(Debug)]
+pub struct Terms {
+    /// E.g. "book"
+    pub item: &'static str,
+    /// E.g. "books"
+    pub items: &'static str,
+    /// Button picking one of two items, e.g. "Read this first"
+    pub preference: &'static str,
+    /// One item having to come before another, e.g. "Read before"
+    pub precedence: &'static str,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SignInMethod {
+    /// E-mail and password, entered in Godric
+    Credentials,
+    /// By the user, in the browser window
+    Browser,
+}
+
+/// How far signing in got
+#[derive(Clone, Debug)]
+pub enum SignIn {
+    Done { user_id: String },
+    Challenge(Challenge),
+}
+
+/// Something a source wants from the user before letting them sign in
+#[derive(Clone)]
+pub enum Challenge {
+    /// A one-time password from an authenticator app, text message or e-mail
+    Otp,
+    /// The characters shown in an image, as PNG
+    Captcha { image: Vec<u8> },
+    /// Anything else, like a puzzle, which has to be solved in the browser window. Signing in carries on by itself
+    /// once it's solved.
+    InBrowser,
+}
+
+impl std::fmt::Debug for Challenge {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Otp => write!(f, "Otp"),
+            // The image itself would only clutter the logs
+            Self::Captcha { image } => write!(f, "Captcha {{ image: {} bytes }}", image.len()),
+            Self::InBrowser => write!(f, "InBrowser"),
+        }
+    }
+}
+
+/// Entry of a list, as known from the list itself
+#[derive(Clone, Debug)]
+pub struct Item {
+    /// Identifies the item within its source, e.g. the URL of a book or the app ID of a game
+    pub id: String,
+    pub title: String,
+    pub date_added: Option<chrono::NaiveDate>,
+    /// For sources that know everything about their items from the list already
+    pub details: Option<Details>,
+}
+
+/// Everything shown of an item while sorting
+#[derive(Clone, Debug, Default)]
+pub struct Details {
+    pub title: String,
+    /// Shown below the title, e.g. the author
+    pub subtitle: String,
+    pub description: String,
+    /// Cover, capsule or the like
+    pub image_url: Option<url::Url>,
+    /// Downloaded from `image_url` by `fetch_item`
+    pub image: Option<Image>,
+    /// Short facts shown below the subtitle, e.g. "309 pages"
+    pub facts: Vec<String>,
+    pub series: Option<Series>,
+    /// Values of the sort keys of the source
+    pub values: Vec<(SortKey, Value)>,
+}
+
+impl Details {
+    pub fn value(&self, key: SortKey) -> Option<Value> {
+        self.values
+            .iter()
+            .find(|(other, _)| *other == key)
+            .map(|(_, value)| value.clone())
+    }
+}
+
+/// Raw data of an image, e.g. a JPEG, as downloaded
+#[derive(Clone, Default)]
+pub struct Image(pub Vec<u8>);
+
+impl std::fmt::Debug for Image {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // The data itself would only clutter the logs
+        write!(f, "Image({} bytes)", self.0.len())
+    }
+}
+
+/// Series an item is part of. Earlier parts have to come before later ones.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Series {
+    pub name: String,
+    /// Tells series with the same name apart, e.g. the URL of the series
+    pub id: String,
+    /// Position within the series. Not necessarily a whole number, as there are e.g. novellas numbered "2.5"
+    pub number: Option<f64>,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum Error {
+    #[error("Browser step failed, screenshot and page source saved to {}", capture.display())]
+    Captured {
+        capture: std::path::PathBuf,
+        #[source]
+        source: color_eyre::Report,
+    },
+    /// The site turned down what the user entered, e.g. a wrong password. There's nothing to debug, so the page isn't
+    /// captured.
+    #[error("{0}")]
+    Rejected(String),
+    /// Signing in needs the user to solve something in the browser window, but it's hidden
+    #[error("Signing in needs a visible browser window")]
+    NeedsVisibleBrowser,
+    #[error(transparent)]
+    WebDriver(#[from] webdriver::Error),
+    #[error(transparent)]
+    Other(#[from] color_eyre::Report),
+}
+
+/// Failure to fetch the details of an item. Unlike `Error`, this can be cloned, so it can be shown for every item.
+#[derive(Clone, Debug, thiserror::Error)]
+pub enum FetchError {
+    /// A failed HTTP request. Transient failures, such as timeouts or an overloaded server, may succeed when
+    /// retried.
+    #[error("{context}: {message}")]
+    Request {
+        context: String,
+        message: String,
+        transient: bool,
+    },
+    #[error("Download cancelled")]
+    Cancelled,
+    #[error("{0}")]
+    Other(String),
+}
+
+impl FetchError {
+    pub fn request(context: &str, error: reqwest::Error) -> Self {
+        let transient = error.is_timeout()
+            || error.is_connect()
+            || error.is_body()
+            || error.status().is_some_and(|status| {
+                status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+            });
+
+        Self::Request {
+            context: context.to_string(),
+            message: error.to_string(),
+            transient,
+        }
+    }
+
+    pub fn is_transient(&self) -> bool {
+        matches!(
+            self,
+            Self::Request {
+                transient: true,
+                ..
+            }
+        )
+    }
+}
+
+impl From<color_eyre::eyre::ErrReport> for FetchError {
+    fn from(error: color_eyre::eyre::ErrReport) -> Self {
+        // Keep the whole chain of causes, as the report itself can't be cloned
+        Self::Other(
+            error
+                .chain()
+                .map(|cause| cause.to_string())
+                .collect::<Vec<_>>()
+                .join(": "),
+        )
+    }
+}
+
+/// Fetches the details of `item`, retrying with exponential backoff as long as the failures are transient
+pub async fn fetch_details_with_retries(
+    source: &dyn Source,
+    item: &Item,
+) -> Result<Details, FetchError> {
+    let mut attempt = 1;
+    loop {
+        match source.fetch_details(item).await {
+            Err(error) if error.is_transient() && attempt < FETCH_ATTEMPTS => {
+                tracing::warn!(item = item.id, attempt, "Retrying item: {error}");
+                // Some jitter, so that items failing together don't retry in lockstep
+                let delay = RETRY_DELAY * 2u32.pow(attempt - 1);
+                let jitter = delay.mul_f64(0.25 * rand::random::<f64>());
+                tokio::time::sleep(delay + jitter).await;
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Fetches the details of `item` along with its image. An image that can't be downloaded is left out, as the
+/// item is of use without it.
+pub async fn fetch_item(
+    source: &dyn Source,
+    item: &Item,
+    image_cache: &std::path::Path,
+) -> Result<Details, FetchError> {
+    let mut details = fetch_details_with_retries(source, item).await?;
+    if let Some(url) = &details.image_url {
+        match fetch_image(url, image_cache).await {
+            Ok(image) => details.image = Some(Image(image)),
+            Err(error) => tracing::warn!(item = item.id, "Unable to fetch image: {error}"),
+        }
+    }
+    Ok(details)
+}
+
+/// Downloads the image of an item, keeping it in `cache` so it's only downloaded once
+async fn fetch_image(url: &url::Url, cache: &std::path::Path) -> Result<Vec<u8>, FetchError> {
+    http::scheduler()
+        .get_cached(url.as_str(), cache)
+        .await
+        .map_err(|error| FetchError::request("Failed to download image", error))
+}
+
+/// Saves the page for debugging, if `result` is a failed browser step
+pub async fn capture_on_failure<T>(
+    session: &Session,
+    result: Result<T, Error>,
+) -> Result<T, Error> {
+    let error = match result {
+        Err(Error::Other(error)) => error,
+        Err(Error::WebDriver(error)) => error.into(),
+        result => return result,
+    };
+
+    match browser::capture_page(session.driver()).await {
+        Ok(capture) => Err(Error::Captured {
+            capture,
+            source: error,
+        }),
+        Err(capture_error) => {
+            tracing::warn!("Unable to capture failed page: {capture_error:#}");
+            Err(Error::Other(error))
+        }
+    }
+}
@@ -0,0 +1,85 @@
+mod book;
+mod shelf;
+mod sign_in;
+
+use futures::future::BoxFuture;
+
+use crate::{
+    backend,
+    common::{
+        config::Config,
+        helpers::{Credentials, Secret},
+        sorting::SortKey,
+        webdriver::Session,
+    },
+    source::{Details, Error, FetchError, Item, SignIn, SignInMethod, Source, Terms},
+};
+
+/// Books on a Goodreads shelf, e.g. "to-read"
+pub struct Goodreads;
+
+const TERMS: Terms = Terms {
+    item: "book",
+    items: "books",
+    preference: "Read this first",
+    precedence: "Read before",
+};
+
+impl Source for Goodreads {
+    fn name(&self) -> &'static str {
+        "Goodreads"
+    }
+
+    fn terms(&self) -> &'static Terms {
+        &TERMS
+    }
+
+    fn sign_in_method(&self) -> SignInMethod {
+        SignInMethod::Credentials
+    }
+
+    fn sort_keys(&self) -> &'static [SortKey] {
+        &SortKey::BOOKS
+    }
+
+    fn login<'a>(
+        &'a self,
+        session: &'a Session,
+        credentials: &'a Credentials,
+        answer: Option<&'a Secret>,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<SignIn, Error>> {
+        Box::pin(async move {
+            let signed_in = match answer {
+                None => sign_in::sign_in_to_goodreads(session, credentials, progress).await,
+                Some(answer) => {
+                    sign_in::answer_challenge(session, credentials, answer, progress).await
+                }
+            };
+            super::capture_on_failure(session, signed_in).await
+        })
+    }
+
+    fn fetch_items<'a>(
+        &'a self,
+        user_id: &'a str,
+        config: &'a Config,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<Vec<Item>, Error>> {
+        Box::pin(async move {
+            shelf::fetch_books(user_id, &config.default_shelf, |fetched, total| {
+                progress.report(backend::welcome::Output::ItemsFetched { fetched, total })
+            })
+            .await
+        })
+    }
+
+    fn fetch_details<'a>(&'a self, item: &'a Item) -> BoxFuture<'a, Result<Details, FetchError>> {
+        Box::pin(async move {
+            let url = url::Url::parse(&item.id)
+                .map_err(|error| FetchError::Other(format!("Invalid book link: {error}")))?;
+            let book = book::Book::fetch(url).await?;
+            Ok(book.into())
+        })
+    }
+}
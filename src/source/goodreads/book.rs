@@ -0,0 +1,193 @@
+use color_eyre::eyre::{ContextCompat, Result};
+use scraper::Html;
+
+use crate::{
+    common::{
+        http, selectors,
+        sorting::{SortKey, Value},
+    },
+    source::{Details, FetchError as Error, Series},
+};
+
+/// Book page on Goodreads
+#[derive(Clone, Debug)]
+pub struct Book {
+    pub url: url::Url,
+    pub title: String,
+    pub author: String,
+    pub blurb: String,
+    pub cover: Option<url::Url>,
+    pub series: Option<Series>,
+    pub page_count: Option<u32>,
+    pub rating: Option<f64>,
+    pub publication_year: Option<i32>,
+}
+
+impl Book {
+    /// Fetches the book page at `url`
+    #[tracing::instrument(name = "book", skip_all, fields(%url))]
+    pub async fn fetch(url: url::Url) -> Result<Self, Error> {
+        let page = http::scheduler()
+            .get_text(url.clone())
+            .await
+            .map_err(|error| Error::request("Unable to load book page", error))?;
+
+        let (title, author, blurb, cover, series, page_count, rating, publication_year) = {
+            let selectors = selectors::selectors();
+            let selectors = &selectors.book;
+            let page = Html::parse_document(&page);
+
+            let title = page
+                .select(&selectors.title)
+                .next()
+                .context("Failed to select title")?
+                .inner_html()
+                .trim()
+                .to_string();
+
+            let author = page
+                .select(&selectors.author)
+                .next()
+                .context("Failed to select author")?
+                .inner_html()
+                .trim()
+                .to_string();
+
+            let blurb = page
+                .select(&selectors.blurb)
+                .next()
+                .context("Failed to select blurb")?
+                .inner_html()
+                .trim()
+                .to_string();
+
+            let image_source = page
+                .select(&selectors.cover)
+                .next()
+                .context("Failed to select cover image")?
+                .attr("src")
+                .context("Failed to obtain cover image source")?;
+            let cover = url.join(image_source).ok();
+
+            // Optional metadata. A book missing any of these is still perfectly usable.
+            // Series are shown as e.g. "Harry Potter #1"
+            let series = page.select(&selectors.series).next().and_then(|series| {
+                let url = url.join(series.attr("href")?).ok()?;
+                let text = series.text().collect::<String>();
+                let (name, number) = match text.rsplit_once('#') {
+                    Some((name, number)) => (name, number.trim().parse().ok()),
+                    None => (text.as_str(), None),
+                };
+                Some(Series {
+                    name: name.trim().to_string(),
+                    id: url.to_string(),
+                    number,
+                })
+            });
+
+            // Shown as e.g. "309 pages, Paperback"
+            let page_count = page.select(&selectors.page_count).next().and_then(|pages| {
+                pages
+                    .text()
+                    .collect::<String>()
+                    .split_whitespace()
+                    .next()?
+                    .parse()
+                    .ok()
+            });
+
+            let rating = page
+                .select(&selectors.rating)
+                .next()
+                .and_then(|rating| rating.text().collect::<String>().trim().parse().ok());
+
+            // Shown as e.g. "First published June 26, 1997"
+            let publication_year =
+                page.select(&selectors.publication_info)
+                    .next()
+                    .and_then(|publication| {
+                        publication
+                            .text()
+                            .collect::<String>()
+                            .split_whitespace()
+                            .last()?
+                            .parse()
+                            .ok()
+                    });
+
+            (
+                title,
+                author,
+                blurb,
+                cover,
+                series,
+                page_count,
+                rating,
+                publication_year,
+            )
+        };
+
+        Ok(Self {
+            url,
+            title,
+            author,
+            blurb,
+            cover,
+            series,
+            page_count,
+            rating,
+            publication_year,
+        })
+    }
+
+    /// Value used for automatic sorting. The date a book was added is a property of the shelf, not the book,
+    /// so it isn't available here.
+    fn sort_value(&self, key: SortKey) -> Option<Value> {
+        match key {
+            SortKey::SeriesNumber => self
+                .series
+                .as_ref()
+                .and_then(|series| series.number)
+                .map(Value::Number),
+            SortKey::DateAdded => None,
+            SortKey::PageCount => self.page_count.map(|pages| Value::Number(pages.into())),
+            SortKey::Rating => self.rating.map(Value::Number),
+            SortKey::PublicationYear => {
+                self.publication_year.map(|year| Value::Number(year.into()))
+            }
+            SortKey::Title => Some(Value::Text(self.title.to_lowercase())),
+            SortKey::Author => Some(Value::Text(self.author.to_lowercase())),
+            SortKey::Price | SortKey::ReleaseDate => None,
+        }
+    }
+}
+
+impl From<Book> for Details {
+    fn from(book: Book) -> Self {
+        let values = SortKey::BOOKS
+            .iter()
+            .filter_map(|&key| Some((key, book.sort_value(key)?)))
+            .collect();
+
+        let facts = [
+            book.page_count.map(|pages| format!("{pages} pages")),
+            book.rating.map(|rating| format!("Rated {rating:.2}")),
+            book.publication_year
+                .map(|year| format!("First published {year}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Self {
+            title: book.title,
+            subtitle: book.author,
+            description: book.blurb,
+            image_url: book.cover,
+            image: None,
+            facts,
+            series: book.series,
+            values,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{
+    common::{http, selectors},
+    source::{Error, Item},
+};
+use color_eyre::{
+    Result,
+    eyre::{Context, ContextCompat},
+};
+use futures::StreamExt;
+use scraper::Html;
+
+/// Fetches a shelf of a user, e.g. "to-read". `on_page_fetched` is called with the number of pages fetched so far
+/// and the total number of pages.
+#[tracing::instrument(skip(on_page_fetched))]
+pub async fn fetch_books(
+    user_id: &str,
+    shelf: &str,
+    on_page_fetched: impl Fn(usize, usize),
+) -> Result<Vec<Item>, Error> {
+    let mut bookshelf_link =
+        url::Url::parse(&format!("https://www.goodreads.com/review/list/{user_id}"))
+            .context("Unable to create link to reading list")?;
+    bookshelf_link.query_pairs_mut().append_pair("shelf", shelf);
+
+    let scheduler = http::scheduler();
+    let bookshelf = scheduler
+        .get_text(bookshelf_link.clone())
+        .await
+        .context("Unable to load bookshelf")?;
+
+    // The scheduler limits how many pages are requested at once, so we don't DOS Amazon
+    let page_count = parse_bookshelf_page_count(&bookshelf)?;
+    let mut pages: futures::stream::FuturesUnordered<_> = (1..=page_count)
+        .map(|i| {
+            let mut link = bookshelf_link.clone();
+            link.query_pairs_mut().append_pair("page", &i.to_string());
+            let scheduler = scheduler.clone();
+            async move {
+                let bookshelf = scheduler
+                    .get_text(link)
+                    .await
+                    .context("Unable to load bookshelf")?;
+                parse_bookshelf_page_books(&bookshelf)
+            }
+        })
+        .collect();
+
+    let mut books = vec![];
+    let mut fetched = 0;
+    while let Some(page) = pages.next().await {
+        books.append(&mut page?);
+        fetched += 1;
+        tracing::debug!("Fetched bookshelf page {fetched}/{page_count}");
+        on_page_fetched(fetched, page_count);
+    }
+
+    // Flatten to one big result, and sort collection of books according to user sorting
+    let mut books: Vec<(usize, Item)> = books.into_iter().collect::<Result<_, _>>()?;
+    books.sort_by(|a, b| a.0.cmp(&b.0));
+    let books: Vec<_> = books.into_iter().map(|entry| entry.1).collect();
+
+    Ok(books)
+}
+
+fn parse_bookshelf_page_count(page: &str) -> Result<usize, Error> {
+    let selectors = selectors::selectors();
+    let selectors = &selectors.shelf;
+    let html = Html::parse_document(page);
+    let bookshelf = html
+        .select(&selectors.content)
+        .next()
+        .context("Failed to read bookshelf content")?;
+
+    let page_count = {
+        let menu = bookshelf.select(&selectors.pagination).next();
+
+        match menu {
+            None => 1, // If we can't find the page navigation menu, we assume that there's only one single page
+            Some(menu) => {
+                let count = menu.select(&selectors.pagination_link).count();
+                let pages = menu
+                    .select(&selectors.pagination_link)
+                    .nth(count - 2)
+                    .context("Failed to count bookshelf pages")?
+                    .inner_html()
+                    .trim()
+                    .parse()
+                    .context("Failed to parse bookshelf page count")?;
+                pages
+            }
+        }
+    };
+    Ok(page_count)
+}
+
+/// Books of a bookshelf page along with their position on the shelf. A book that fails to parse doesn't spoil the rest.
+type PageBooks = Vec<Result<(usize, Item), Error>>;
+
+fn parse_bookshelf_page_books(page: &str) -> Result<PageBooks, Error> {
+    let selectors = selectors::selectors();
+    let selectors = &selectors.shelf;
+    let html = Html::parse_document(page);
+    let bookshelf = html
+        .select(&selectors.content)
+        .next()
+        .context("Failed to read bookshelf content")?;
+
+    let books: Vec<_> = bookshelf.select(&selectors.book_row).collect();
+
+    let books = books
+        .iter()
+        .map(|book_row| {
+            let position: usize = book_row
+                .select(&selectors.position)
+                .next()
+                .context("Unable to obtain book position")?
+                .inner_html()
+                .trim()
+                .parse()
+                .context("Failed to parse book position")?;
+
+            let book = book_row
+                .select(&selectors.title)
+                .next()
+                .context("Unable to obtain book info")?;
+            let title = book
+                .text()
+                .next()
+                .context("Unable to obtain book title")?
+                .trim()
+                .to_string();
+            let link = book.attr("href").context("Failed to obtain book link")?;
+            let link = url::Url::parse("https://www.goodreads.com")
+                .unwrap()
+                .join(link)
+                .context("Failed to create book link")?;
+
+            // Shown as e.g. "Mar 15, 2023", with the full date in the title attribute
+            let date_added = book_row
+                .select(&selectors.date_added)
+                .next()
+                .and_then(|date| date.attr("title"))
+                .and_then(|date| chrono::NaiveDate::parse_from_str(date.trim(), "%B %d, %Y").ok());
+
+            Ok((
+                position,
+                Item {
+                    id: link.to_string(),
+                    title,
+                    date_added,
+                    details: None,
+                },
+            ))
+        })
+        .collect();
+
+    Ok(books)
+}
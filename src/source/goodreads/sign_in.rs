@@ -0,0 +1,269 @@
+use crate::{
+    backend,
+    common::{
+        helpers::{Credentials, Secret},
+        webdriver::{Element, Session},
+    },
+    source::{Challenge, Error, SignIn},
+};
+use std::time::Duration;
+
+use color_eyre::{Result, eyre::ContextCompat, eyre::eyre};
+use thirtyfour as tf;
+
+/// How long the user gets to solve a challenge in the browser window
+const BROWSER_CHALLENGE_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Elements of the sign in pages of Goodreads and Amazon, located as configured in the selector file
+mod elements {
+    use crate::common::{
+        selectors::{Locator, selectors},
+        webdriver::Element,
+    };
+
+    fn element(name: &'static str, locators: &[Locator]) -> Element {
+        Element::new(name, locators.iter().map(thirtyfour::By::from))
+    }
+
+    pub fn email_sign_in_button() -> Element {
+        element(
+            "e-mail sign in button",
+            &selectors().sign_in.email_sign_in_button,
+        )
+    }
+
+    pub fn email_field() -> Element {
+        element("e-mail field", &selectors().sign_in.email_field)
+    }
+
+    pub fn password_field() -> Element {
+        element("password field", &selectors().sign_in.password_field)
+    }
+
+    pub fn sign_in_button() -> Element {
+        element("sign in button", &selectors().sign_in.sign_in_button)
+    }
+
+    pub fn profile_menu() -> Element {
+        element("profile menu", &selectors().sign_in.profile_menu)
+    }
+
+    pub fn otp_field() -> Element {
+        element("one-time password field", &selectors().sign_in.otp_field)
+    }
+
+    pub fn otp_submit_button() -> Element {
+        element(
+            "one-time password submit button",
+            &selectors().sign_in.otp_submit_button,
+        )
+    }
+
+    pub fn captcha_image() -> Element {
+        element("CAPTCHA image", &selectors().sign_in.captcha_image)
+    }
+
+    pub fn captcha_field() -> Element {
+        element("CAPTCHA field", &selectors().sign_in.captcha_field)
+    }
+
+    pub fn error_message() -> Element {
+        element("error message", &selectors().sign_in.error_message)
+    }
+}
+
+/// Page shown after submitting something on the sign in pages
+enum Page {
+    SignedIn,
+    Otp,
+    Captcha,
+    /// Amazon rejected what was submitted, e.g. a wrong password
+    Rejected(String),
+}
+
+impl Page {
+    /// Elements telling the pages apart, in the order of `Page::from_index`
+    fn markers() -> [Element; 4] {
+        [
+            elements::profile_menu(),
+            elements::otp_field(),
+            elements::captcha_image(),
+            elements::error_message(),
+        ]
+    }
+
+    async fn from_index(index: usize, found: &tf::WebElement) -> Self {
+        match index {
+            0 => Page::SignedIn,
+            1 => Page::Otp,
+            2 => Page::Captcha,
+            _ => {
+                let message = found.text().await.unwrap_or_default();
+                Page::Rejected(message.split_whitespace().collect::<Vec<_>>().join(" "))
+            }
+        }
+    }
+}
+
+/// Signs in to goodreads.com, unless Amazon has a challenge for the user first
+#[tracing::instrument(skip_all)]
+pub async fn sign_in_to_goodreads(
+    session: &Session,
+    credentials: &Credentials,
+    progress: &backend::Progress,
+) -> Result<SignIn, Error> {
+    let url = url::Url::parse("https://www.goodreads.com/user/sign_in")
+        .expect("Failed to parse URL for Goodreads sign in page");
+    session.goto("open sign in page", &url).await?;
+
+    session
+        .click(
+            "choose signing in with e-mail",
+            &elements::email_sign_in_button(),
+        )
+        .await?;
+
+    session
+        .type_into("enter e-mail", &elements::email_field(), &credentials.email)
+        .await?;
+    session
+        .type_into(
+            "enter password",
+            &elements::password_field(),
+            credentials.password.expose(),
+        )
+        .await?;
+
+    let sign_in_button = elements::sign_in_button();
+    let clicked = session.click("submit sign in", &sign_in_button).await?;
+    session
+        .wait_until_gone("wait for next page", &sign_in_button, &clicked)
+        .await?;
+
+    after_submit(session, progress).await
+}
+
+/// Enters the answer to the challenge currently shown, and submits it
+#[tracing::instrument(skip_all)]
+pub async fn answer_challenge(
+    session: &Session,
+    credentials: &Credentials,
+    answer: &Secret,
+    progress: &backend::Progress,
+) -> Result<SignIn, Error> {
+    let (field, submit) = match current_page(session).await? {
+        Some(Page::Otp) => (elements::otp_field(), elements::otp_submit_button()),
+        Some(Page::Captcha) => {
+            // The CAPTCHA page asks for the password again
+            if session
+                .find_now("look for password field", &elements::password_field())
+                .await?
+                .is_some()
+            {
+                session
+                    .type_into(
+                        "enter password",
+                        &elements::password_field(),
+                        credentials.password.expose(),
+                    )
+                    .await?;
+            }
+            (elements::captcha_field(), elements::sign_in_button())
+        }
+        _ => return Err(eyre!("No challenge to answer on the current page").into()),
+    };
+
+    session
+        .type_into("enter challenge answer", &field, answer.expose())
+        .await?;
+    let clicked = session.click("submit challenge answer", &submit).await?;
+    session
+        .wait_until_gone("wait for next page", &submit, &clicked)
+        .await?;
+
+    after_submit(session, progress).await
+}
+
+/// Figures out where submitting a sign in page led. Challenges the user can answer in the app are handed back,
+/// anything else is left for the user to solve in the browser window.
+async fn after_submit(session: &Session, progress: &backend::Progress) -> Result<SignIn, Error> {
+    let page = match wait_for_page(session, session.waits().timeout).await? {
+        Some(page) => page,
+        None => {
+            // Still on one of Amazon's sign in pages, but not one we know
+            let url = session
+                .step("read current URL", session.driver().current_url())
+                .await?;
+            if !url.path().starts_with("/ap/") {
+                return Err(eyre!("Unexpected page after signing in: {url}").into());
+            }
+            if session.is_headless() {
+                return Err(Error::NeedsVisibleBrowser);
+            }
+
+            progress.report(backend::welcome::Output::ChallengeRequired(
+                Challenge::InBrowser,
+            ));
+            wait_for_page(session, BROWSER_CHALLENGE_TIMEOUT)
+                .await?
+                .context("Challenge wasn't solved in time")?
+        }
+    };
+
+    match page {
+        Page::SignedIn => Ok(SignIn::Done {
+            user_id: user_id(session).await?,
+        }),
+        Page::Otp => Ok(SignIn::Challenge(Challenge::Otp)),
+        Page::Captcha => {
+            let image = session
+                .find("find CAPTCHA", &elements::captcha_image())
+                .await?;
+            let image = session
+                .step("screenshot CAPTCHA", image.screenshot_as_png())
+                .await?;
+            Ok(SignIn::Challenge(Challenge::Captcha { image }))
+        }
+        Page::Rejected(message) => Err(Error::Rejected(format!(
+            "Amazon rejected sign in: {message}"
+        ))),
+    }
+}
+
+/// Waits for the current page to be one we know, for at most `timeout`
+async fn wait_for_page(session: &Session, timeout: Duration) -> Result<Option<Page>, Error> {
+    match session
+        .first_of("recognize page", &Page::markers(), timeout)
+        .await?
+    {
+        Some((index, found)) => Ok(Some(Page::from_index(index, &found).await)),
+        None => Ok(None),
+    }
+}
+
+async fn current_page(session: &Session) -> Result<Option<Page>, Error> {
+    wait_for_page(session, Duration::ZERO).await
+}
+
+/// Reads the user ID-string from the profile menu
+async fn user_id(session: &Session) -> Result<String, Error> {
+    // Find user ID and construct link to "want to read" list
+    // https://www.goodreads.com/user/show/176878294-testy-mctestface
+    let profile_button = session
+        .find("find user profile", &elements::profile_menu())
+        .await?;
+    let user = session
+        .step("read user profile link", profile_button.attr("href"))
+        .await?
+        .context("Unable to find user ID")?
+        .split('/')
+        .last()
+        .context("Unable to parse user ID")?
+        .to_owned();
+    let user_id = user
+        .split('-')
+        .next()
+        .context("Unable to extract user ID number")?
+        .to_owned();
+    Ok(user_id)
+}
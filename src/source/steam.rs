@@ -0,0 +1,104 @@
+mod sign_in;
+mod wishlist;
+
+use color_eyre::eyre::Context;
+use futures::future::BoxFuture;
+
+use crate::{
+    backend,
+    common::{
+        config::Config,
+        helpers::{Credentials, Secret},
+        sorting::SortKey,
+        webdriver::Session,
+    },
+    source::{Details, Error, FetchError, Item, SignIn, SignInMethod, Source, Terms},
+};
+
+/// Games on a Steam wishlist
+pub struct Steam;
+
+const TERMS: Terms = Terms {
+    item: "game",
+    items: "games",
+    preference: "Want this more",
+    precedence: "Want before",
+};
+
+impl Source for Steam {
+    fn name(&self) -> &'static str {
+        "Steam"
+    }
+
+    fn terms(&self) -> &'static Terms {
+        &TERMS
+    }
+
+    fn sign_in_method(&self) -> SignInMethod {
+        SignInMethod::Browser
+    }
+
+    fn sort_keys(&self) -> &'static [SortKey] {
+        &SortKey::GAMES
+    }
+
+    fn login<'a>(
+        &'a self,
+        session: &'a Session,
+        _credentials: &'a Credentials,
+        _answer: Option<&'a Secret>,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<SignIn, Error>> {
+        Box::pin(async move {
+            super::capture_on_failure(session, sign_in::sign_in(session, progress).await).await
+        })
+    }
+
+    fn fetch_items<'a>(
+        &'a self,
+        user_id: &'a str,
+        config: &'a Config,
+        progress: &'a backend::Progress,
+    ) -> BoxFuture<'a, Result<Vec<Item>, Error>> {
+        Box::pin(async move {
+            let games =
+                wishlist::fetch_wishlist(user_id, &config.steam_country, |fetched, total| {
+                    progress.report(backend::welcome::Output::ItemsFetched { fetched, total })
+                })
+                .await?;
+            Ok(games.into_iter().map(Item::from).collect())
+        })
+    }
+
+    fn fetch_details<'a>(&'a self, item: &'a Item) -> BoxFuture<'a, Result<Details, FetchError>> {
+        // The wishlist comes with everything there is to know about its games
+        Box::pin(async move {
+            item.details
+                .clone()
+                .ok_or_else(|| FetchError::Other(format!("No store details for {}", item.title)))
+        })
+    }
+
+    fn can_apply_order(&self) -> bool {
+        true
+    }
+
+    fn apply_order<'a>(
+        &'a self,
+        session: &'a Session,
+        user_id: &'a str,
+        items: &'a [Item],
+    ) -> BoxFuture<'a, Result<(), Error>> {
+        Box::pin(async move {
+            let order = items
+                .iter()
+                .map(|item| {
+                    item.id
+                        .parse()
+                        .with_context(|| format!("Invalid app ID: {}", item.id))
+                })
+                .collect::<color_eyre::Result<Vec<u32>>>()?;
+            wishlist::apply_order(session, user_id, &order).await
+        })
+    }
+}
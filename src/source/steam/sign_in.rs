@@ -0,0 +1,63 @@
+use std::time::Duration;
+
+use color_eyre::eyre::eyre;
+
+use crate::{
+    backend,
+    common::webdriver::Session,
+    source::{Challenge, Error, SignIn},
+};
+
+/// How long the user gets to sign in in the browser window
+const SIGN_IN_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+
+/// Waits for the user to sign in to the Steam store, unless the browser is signed in already. The user ID is the
+/// Steam ID of the user.
+#[tracing::instrument(skip_all)]
+pub async fn sign_in(session: &Session, progress: &backend::Progress) -> Result<SignIn, Error> {
+    let store = url::Url::parse("https://store.steampowered.com/")
+        .expect("Failed to parse URL for Steam store");
+    session.goto("open store", &store).await?;
+    if let Some(user_id) = steam_id(session).await? {
+        return Ok(SignIn::Done { user_id });
+    }
+
+    if session.is_headless() {
+        return Err(Error::NeedsVisibleBrowser);
+    }
+
+    let sign_in = url::Url::parse("https://store.steampowered.com/login/")
+        .expect("Failed to parse URL for Steam sign in page");
+    session.goto("open sign in page", &sign_in).await?;
+    // Steam Guard makes signing in for the user impractical, so they sign in in the browser window
+    progress.report(backend::welcome::Output::ChallengeRequired(
+        Challenge::InBrowser,
+    ));
+
+    let deadline = tokio::time::Instant::now() + SIGN_IN_TIMEOUT;
+    loop {
+        tokio::time::sleep(session.waits().interval).await;
+        // Scripts may fail while the user is moving between pages
+        if let Ok(Some(user_id)) = steam_id(session).await {
+            return Ok(SignIn::Done { user_id });
+        }
+        if tokio::time::Instant::now() >= deadline {
+            return Err(eyre!("Not signed in within {SIGN_IN_TIMEOUT:?}").into());
+        }
+    }
+}
+
+/// Steam ID of the signed in user, which store pages hand to their scripts. `None` while signed out, or when not on
+/// a store page.
+async fn steam_id(session: &Session) -> Result<Option<String>, Error> {
+    let steam_id = session
+        .step(
+            "read Steam ID",
+            session.driver().execute(
+                "return typeof g_steamID === 'string' ? g_steamID : null;",
+                Vec::new(),
+            ),
+        )
+        .await?;
+    Ok(steam_id.json().as_str().map(String::from))
+}
@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+
+use chrono::Datelike;
+use color_eyre::{
+    Result,
+    eyre::{Context, eyre},
+};
+
+use crate::{
+    common::{http, sorting::SortKey, sorting::Value, webdriver::Session},
+    source::{Details, Error, Item},
+};
+
+/// Prefix of the URLs of store assets, like capsule images
+const ASSET_URL: &str = "https://shared.akamai.steamstatic.com/store_item_assets/";
+
+/// Games asked for per request for store details. Steam rejects requests asking for too many at once.
+const DETAILS_BATCH_SIZE: usize = 50;
+
+/// Reorders a wishlist the way the wishlist page does. Runs in the browser, as it needs the signed in session.
+/// Takes the Steam ID and the app IDs in their new order, and hands the HTTP status, or the error, to the callback.
+const REORDER_SCRIPT: &str = r#"
+const [steamId, appIds, done] = arguments;
+const body = new URLSearchParams();
+body.append("sessionid", g_sessionID);
+for (const appId of appIds) {
+    body.append("wishlist[]", appId);
+}
+fetch(`https://store.steampowered.com/wishlist/profiles/${steamId}/reorder/`, {
+    method: "POST",
+    body,
+    credentials: "include",
+}).then(response => done(response.status), error => done(String(error)));
+"#;
+
+/// Game on a wishlist, with what the store says about it
+#[derive(Clone, Debug)]
+pub struct Game {
+    pub app_id: u32,
+    pub name: String,
+    pub description: String,
+    /// Capsule image shown in store listings
+    pub capsule: Option<url::Url>,
+    /// Missing for games that can't be bought (yet)
+    pub price: Option<Price>,
+    /// Most fitting first
+    pub tags: Vec<String>,
+    /// When the game was released, or is planned to be released
+    pub release_date: Option<chrono::NaiveDate>,
+    pub date_added: Option<chrono::NaiveDate>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Price {
+    /// As shown in the store, e.g. "19,99€"
+    pub formatted: String,
+    /// For comparing prices, in the smallest unit of the currency
+    pub cents: u64,
+}
+
+impl std::fmt::Display for Price {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.formatted)
+    }
+}
+
+/// Web API responses wrap their content in a `response` field
+#[derive(serde::Deserialize)]
+struct Response<T> {
+    response: T,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct WishlistResponse {
+    items: Vec<WishlistItem>,
+}
+
+#[derive(serde::Deserialize)]
+struct WishlistItem {
+    appid: u32,
+    /// Position on the wishlist, starting at 1. Games that were never ranked have priority 0.
+    #[serde(default)]
+    priority: u32,
+    #[serde(default)]
+    date_added: i64,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct StoreItems {
+    store_items: Vec<StoreItem>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct StoreItem {
+    appid: u32,
+    name: String,
+    is_free: bool,
+    basic_info: BasicInfo,
+    tagids: Vec<u32>,
+    assets: Option<Assets>,
+    release: Release,
+    best_purchase_option: Option<PurchaseOption>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct BasicInfo {
+    short_description: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Assets {
+    /// Path of the assets, with `${FILENAME}` standing in for the file
+    asset_url_format: String,
+    main_capsule: Option<String>,
+    header: Option<String>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct Release {
+    steam_release_date: Option<i64>,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct PurchaseOption {
+    formatted_final_price: String,
+    /// 64 bit numbers are sent as strings
+    final_price_in_cents: String,
+}
+
+#[derive(Default, serde::Deserialize)]
+#[serde(default)]
+struct TagList {
+    tags: Vec<Tag>,
+}
+
+#[derive(serde::Deserialize)]
+struct Tag {
+    tagid: u32,
+    name: String,
+}
+
+/// Fetches the wishlist of the user with `steam_id`, most wanted first, along with the store details of every
+/// game. Prices are the ones of the store in `country`. `on_games_fetched` is called with the number of games
+/// fetched so far and the total number of games. Fails for an empty wishlist, as a private one looks the same.
+#[tracing::instrument(skip(on_games_fetched))]
+pub async fn fetch_wishlist(
+    steam_id: &str,
+    country: &str,
+    on_games_fetched: impl Fn(usize, usize),
+) -> Result<Vec<Game>> {
+    let scheduler = http::scheduler();
+
+    let mut link = url::Url::parse("https://api.steampowered.com/IWishlistService/GetWishlist/v1/")
+        .context("Unable to create link to wishlist")?;
+    link.query_pairs_mut().append_pair("steamid", steam_id);
+    let wishlist = scheduler
+        .get_text(link)
+        .await
+        .context("Unable to load wishlist")?;
+    let mut items = serde_json::from_str::<Response<WishlistResponse>>(&wishlist)
+        .context("Failed to parse wishlist")?
+        .response
+        .items;
+    // Steam answers the same for an empty wishlist as for one the public isn't allowed to see. Either way there's
+    // nothing to sort, so explain how to share it.
+    if items.is_empty() {
+        return Err(eyre!(
+            "Your wishlist is empty, or only visible to you. The wishlist is read like a visitor would see it, so \
+             set \"Game details\" to Public in your Steam privacy settings and sign in again."
+        ));
+    }
+    // Games that were never ranked go last, newest first, like on the wishlist page
+    items.sort_by_key(|item| (item.priority == 0, item.priority, -item.date_added));
+
+    let tags = fetch_tag_names(&scheduler).await?;
+
+    let mut details = HashMap::new();
+    let mut fetched = 0;
+    for batch in items.chunks(DETAILS_BATCH_SIZE) {
+        let app_ids: Vec<_> = batch.iter().map(|item| item.appid).collect();
+        for item in fetch_store_items(&scheduler, &app_ids, country).await? {
+            details.insert(item.appid, item);
+        }
+        fetched += batch.len();
+        on_games_fetched(fetched, items.len());
+    }
+
+    Ok(items
+        .into_iter()
+        .map(|item| {
+            let details = details.remove(&item.appid).unwrap_or_default();
+            Game::new(&item, details, &tags)
+        })
+        .collect())
+}
+
+/// Names of the tags, which the store details only refer to by ID
+async fn fetch_tag_names(scheduler: &http::Scheduler) -> Result<HashMap<u32, String>> {
+    let tags = scheduler
+        .get_text("https://api.steampowered.com/IStoreService/GetTagList/v1/?language=english")
+        .await
+        .context("Unable to load tag names")?;
+    let tags = serde_json::from_str::<Response<TagList>>(&tags)
+        .context("Failed to parse tag names")?
+        .response
+        .tags;
+    Ok(tags.into_iter().map(|tag| (tag.tagid, tag.name)).collect())
+}
+
+async fn fetch_store_items(
+    scheduler: &http::Scheduler,
+    app_ids: &[u32],
+    country: &str,
+) -> Result<Vec<StoreItem>> {
+    let request = serde_json::json!({
+        "ids": app_ids.iter().map(|app_id| serde_json::json!({ "appid": app_id })).collect::<Vec<_>>(),
+        "context": { "language": "english", "country_code": country },
+        "data_request": {
+            "include_assets": true,
+            "include_release": true,
+            "include_basic_info": true,
+            "include_tag_count": 10,
+        },
+    });
+    let mut link = url::Url::parse("https://api.steampowered.com/IStoreBrowseService/GetItems/v1/")
+        .context("Unable to create link to store details")?;
+    link.query_pairs_mut()
+        .append_pair("input_json", &request.to_string());
+
+    let items = scheduler
+        .get_text(link)
+        .await
+        .context("Unable to load store details")?;
+    Ok(serde_json::from_str::<Response<StoreItems>>(&items)
+        .context("Failed to parse store details")?
+        .response
+        .store_items)
+}
+
+impl From<Game> for Item {
+    fn from(game: Game) -> Self {
+        let days = |date: chrono::NaiveDate| Value::Number(date.num_days_from_ce().into());
+        let values = [
+            (SortKey::DateAdded, game.date_added.map(days)),
+            (
+                SortKey::Price,
+                game.price
+                    .as_ref()
+                    .map(|price| Value::Number(price.cents as f64)),
+            ),
+            (SortKey::ReleaseDate, game.release_date.map(days)),
+            (SortKey::Title, Some(Value::Text(game.name.to_lowercase()))),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| Some((key, value?)))
+        .collect();
+
+        let facts = [
+            game.price.as_ref().map(ToString::to_string),
+            game.release_date
+                .map(|date| format!("Released {}", date.format("%b %-d, %Y"))),
+            (!game.tags.is_empty()).then(|| game.tags.join(", ")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        Self {
+            id: game.app_id.to_string(),
+            title: game.name.clone(),
+            date_added: game.date_added,
+            details: Some(Details {
+                title: game.name,
+                subtitle: String::new(),
+                description: game.description,
+                image_url: game.capsule,
+                image: None,
+                facts,
+                series: None,
+                values,
+            }),
+        }
+    }
+}
+
+impl Game {
+    fn new(item: &WishlistItem, details: StoreItem, tags: &HashMap<u32, String>) -> Self {
+        let date = |timestamp: i64| {
+            chrono::DateTime::from_timestamp(timestamp, 0).map(|date| date.date_naive())
+        };
+
+        let capsule = details.assets.and_then(|assets| {
+            let file = assets.main_capsule.or(assets.header)?;
+            let path = assets.asset_url_format.replace("${FILENAME}", &file);
+            url::Url::parse(ASSET_URL).ok()?.join(&path).ok()
+        });
+
+        let price = match details.best_purchase_option {
+            Some(option) => option.final_price_in_cents.parse().ok().map(|cents| Price {
+                formatted: option.formatted_final_price,
+                cents,
+            }),
+            None if details.is_free => Some(Price {
+                formatted: "Free".to_string(),
+                cents: 0,
+            }),
+            None => None,
+        };
+
+        Self {
+            app_id: item.appid,
+            // Games that were removed from the store don't have details anymore
+            name: if details.name.is_empty() {
+                format!("App {}", item.appid)
+            } else {
+                details.name
+            },
+            description: details.basic_info.short_description,
+            capsule,
+            price,
+            tags: details
+                .tagids
+                .iter()
+                .filter_map(|tag| tags.get(tag).cloned())
+                .collect(),
+            release_date: details.release.steam_release_date.and_then(date),
+            date_added: (item.date_added > 0)
+                .then(|| date(item.date_added))
+                .flatten(),
+        }
+    }
+}
+
+/// Writes a new order back to the wishlist, as app IDs with the most wanted game first
+#[tracing::instrument(skip(session, order))]
+pub async fn apply_order(session: &Session, steam_id: &str, order: &[u32]) -> Result<(), Error> {
+    // The session ID the script needs is only handed out on store pages
+    let wishlist = url::Url::parse("https://store.steampowered.com/wishlist/")
+        .expect("Failed to parse URL for Steam wishlist");
+    session.goto("open wishlist", &wishlist).await?;
+
+    let status = session
+        .step(
+            "reorder wishlist",
+            session.driver().execute_async(
+                REORDER_SCRIPT,
+                vec![serde_json::json!(steam_id), serde_json::json!(order)],
+            ),
+        )
+        .await?;
+    match status.json().as_u64() {
+        Some(200) => Ok(()),
+        Some(status) => Err(eyre!("Steam rejected the new order with status {status}").into()),
+        None => Err(eyre!("Failed to send the new order: {}", status.json()).into()),
+    }
+}